@@ -0,0 +1,104 @@
+use crate::Clock;
+use core::ops::{Deref, DerefMut};
+
+/// Wraps a node with the time it was created, to catch nodes parked for too long, e.g. in a
+/// forgotten queue during soak tests.
+pub struct Aged<T> {
+    node: T,
+    born: u32,
+}
+
+impl<T> Aged<T> {
+    /// Wraps `node`, starting its age now.
+    pub fn new(node: T, clock: &impl Clock) -> Self {
+        Self {
+            node,
+            born: clock.now(),
+        }
+    }
+
+    /// Returns the ticks elapsed since the node was wrapped.
+    #[inline]
+    pub fn age(&self, clock: &impl Clock) -> u32 {
+        clock.now().wrapping_sub(self.born)
+    }
+
+    /// Checks if the node is older than `max_age` ticks.
+    #[inline]
+    pub fn expired(&self, clock: &impl Clock, max_age: u32) -> bool {
+        self.age(clock) > max_age
+    }
+
+    /// Panics if the node is older than `max_age` ticks.
+    #[track_caller]
+    pub fn assert_max_age(&self, clock: &impl Clock, max_age: u32) {
+        let age = self.age(clock);
+        assert!(
+            age <= max_age,
+            "node is {} ticks old, the maximum is {}",
+            age,
+            max_age
+        );
+    }
+
+    /// Restarts the age of the node, e.g. when it's reused.
+    #[inline]
+    pub fn renew(&mut self, clock: &impl Clock) {
+        self.born = clock.now();
+    }
+
+    /// Returns the wrapped node.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.node
+    }
+}
+
+impl<T> Deref for Aged<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.node
+    }
+}
+
+impl<T> DerefMut for Aged<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Aged;
+    use crate::{typenum::consts::*, DMANodeMut, Node};
+    use core::cell::Cell;
+
+    #[test]
+    fn age() {
+        let time = Cell::new(u32::MAX - 5);
+        let clock = || time.get();
+        let mut node = Aged::new(Node::<U4, u8>::new(), &clock);
+        node.write_slice(&[1]);
+
+        time.set(4);
+        assert_eq!(node.age(&clock), 10);
+        assert!(!node.expired(&clock, 10));
+        assert!(node.expired(&clock, 9));
+        node.assert_max_age(&clock, 10);
+
+        node.renew(&clock);
+        assert_eq!(node.age(&clock), 0);
+        assert_eq!(&node.into_inner()[..], &[1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn assert_max_age() {
+        let time = Cell::new(0);
+        let clock = || time.get();
+        let node = Aged::new(Node::<U4, u8>::new(), &clock);
+        time.set(11);
+        node.assert_max_age(&clock, 10);
+    }
+}
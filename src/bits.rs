@@ -0,0 +1,190 @@
+use crate::{CapacityError, DMANodeMut};
+
+/// Order in which the bits are placed inside each byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The first bit goes into the most significant bit of the byte.
+    MsbFirst,
+    /// The first bit goes into the least significant bit of the byte.
+    LsbFirst,
+}
+
+/// Writes bit fields into a byte node.
+///
+/// Bytes are committed to the node as soon as they are complete, `flush` must be used at the end
+/// to commit the last partial byte, padded with zeros.
+pub struct BitWriter<'a, D> {
+    node: &'a mut D,
+    order: BitOrder,
+    pending: u8,
+    bits: u32,
+}
+
+/// Reads bit fields from a byte slice, usually the contents of a node.
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    order: BitOrder,
+    position: usize,
+}
+
+impl<'a, D: DMANodeMut<u8>> BitWriter<'a, D> {
+    /// Creates a writer appending to `node`.
+    pub fn new(node: &'a mut D, order: BitOrder) -> Self {
+        Self {
+            node,
+            order,
+            pending: 0,
+            bits: 0,
+        }
+    }
+
+    /// Writes the `count` least significant bits of `value`, the most significant of them first
+    /// in `MsbFirst` order and the least significant first in `LsbFirst`. Nothing is written if
+    /// they don't fit in the node.
+    ///
+    /// # Panics
+    ///
+    /// If `count` is greater than 32.
+    pub fn write(&mut self, value: u32, count: u32) -> Result<(), CapacityError> {
+        assert!(count <= 32);
+        // Counts the partial byte too, so `flush` always has space for it
+        let bytes = ((self.bits + count + 7) / 8) as usize;
+        if bytes > self.node.free() {
+            return Err(CapacityError);
+        }
+
+        for i in 0..count {
+            let bit = match self.order {
+                BitOrder::MsbFirst => (value >> (count - 1 - i)) & 1,
+                BitOrder::LsbFirst => (value >> i) & 1,
+            } as u8;
+            self.pending |= match self.order {
+                BitOrder::MsbFirst => bit << (7 - self.bits),
+                BitOrder::LsbFirst => bit << self.bits,
+            };
+            self.bits += 1;
+
+            if self.bits == 8 {
+                self.node.write_slice(&[self.pending]);
+                self.pending = 0;
+                self.bits = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Commits the last partial byte to the node, if any. `write` already makes sure there's space
+    /// for it, so this only fails if the node was modified in between.
+    pub fn flush(&mut self) -> Result<(), CapacityError> {
+        if self.bits != 0 {
+            if self.node.write_slice(&[self.pending]) == 0 {
+                return Err(CapacityError);
+            }
+            self.pending = 0;
+            self.bits = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> BitReader<'a> {
+    /// Creates a reader starting at the first bit of `data`.
+    pub fn new(data: &'a [u8], order: BitOrder) -> Self {
+        Self {
+            data,
+            order,
+            position: 0,
+        }
+    }
+
+    /// Reads `count` bits, in the same order they would be written by a `BitWriter`, `None` is
+    /// returned if there aren't enough bits left.
+    ///
+    /// # Panics
+    ///
+    /// If `count` is greater than 32.
+    pub fn read(&mut self, count: u32) -> Option<u32> {
+        assert!(count <= 32);
+        if self.position + count as usize > self.data.len() * 8 {
+            return None;
+        }
+
+        let mut value = 0u32;
+        for i in 0..count {
+            let byte = self.data[self.position / 8];
+            let shift = (self.position % 8) as u32;
+            let bit = u32::from(
+                match self.order {
+                    BitOrder::MsbFirst => byte >> (7 - shift),
+                    BitOrder::LsbFirst => byte >> shift,
+                } & 1,
+            );
+            value |= match self.order {
+                BitOrder::MsbFirst => bit << (count - 1 - i),
+                BitOrder::LsbFirst => bit << i,
+            };
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    /// Returns how many bits are left to be read.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitOrder, BitReader, BitWriter};
+    use crate::{typenum::consts::*, CapacityError, Node};
+
+    #[test]
+    fn msb_first() {
+        let mut node = Node::<U4, u8>::new();
+        let mut writer = BitWriter::new(&mut node, BitOrder::MsbFirst);
+        writer.write(0b101, 3).unwrap();
+        writer.write(0x1F, 5).unwrap();
+        writer.write(0xABC, 12).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(&node[..], &[0b1011_1111, 0xAB, 0xC0]);
+
+        let mut reader = BitReader::new(&node, BitOrder::MsbFirst);
+        assert_eq!(reader.read(3), Some(0b101));
+        assert_eq!(reader.read(5), Some(0x1F));
+        assert_eq!(reader.read(12), Some(0xABC));
+        assert_eq!(reader.remaining(), 4);
+        assert_eq!(reader.read(5), None);
+    }
+
+    #[test]
+    fn lsb_first() {
+        let mut node = Node::<U8, u8>::new();
+        let mut writer = BitWriter::new(&mut node, BitOrder::LsbFirst);
+        writer.write(0b101, 3).unwrap();
+        writer.write(0x1F, 5).unwrap();
+        writer.write(0xDEAD_BEEF, 32).unwrap();
+        writer.write(1, 1).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(&node[..], &[0b1111_1101, 0xEF, 0xBE, 0xAD, 0xDE, 0x01]);
+
+        let mut reader = BitReader::new(&node, BitOrder::LsbFirst);
+        assert_eq!(reader.read(3), Some(0b101));
+        assert_eq!(reader.read(5), Some(0x1F));
+        assert_eq!(reader.read(32), Some(0xDEAD_BEEF));
+        assert_eq!(reader.read(1), Some(1));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut node = Node::<U1, u8>::new();
+        let mut view = node.as_node_mut();
+        let mut writer = BitWriter::new(&mut view, BitOrder::MsbFirst);
+        writer.write(0, 4).unwrap();
+        assert_eq!(writer.write(0, 12), Err(CapacityError));
+        writer.write(0xF, 4).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(&node[..], &[0x0F]);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{Clock, CodecError, DMANodeMut, RateLimiter, Stage};
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength, GenericArray};
+
+/// `Stage` prefixing each frame with a 1 byte channel id, so several subsystems can share one
+/// link. The channel can be changed between frames to interleave them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChannelMux {
+    channel: u8,
+}
+
+impl ChannelMux {
+    /// Creates a mux tagging the frames with `channel`.
+    pub fn new(channel: u8) -> Self {
+        Self { channel }
+    }
+
+    /// Returns the channel of the next frame.
+    #[inline]
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Changes the channel of the next frames.
+    #[inline]
+    pub fn set_channel(&mut self, channel: u8) {
+        self.channel = channel;
+    }
+}
+
+impl Stage for ChannelMux {
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        output.try_write_vectored(&[&[self.channel], input])?;
+        Ok(())
+    }
+}
+
+/// Reason for `QuotaMux::send` to reject a frame, `output` is left as it was.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaError {
+    /// The channel id is out of the range of the mux.
+    Channel,
+    /// The channel already has `max_in_flight` nodes in flight.
+    InFlight,
+    /// The channel exceeded its byte rate.
+    Rate,
+    /// The tagged frame doesn't fit in the output node.
+    Capacity,
+}
+
+/// Limits of one channel of a `QuotaMux`.
+pub struct ChannelQuota<C> {
+    limiter: RateLimiter<C>,
+    max_in_flight: usize,
+    in_flight: usize,
+}
+
+/// Mux tagging frames like `ChannelMux`, where each channel has its own limits on nodes in flight
+/// and bytes per clock tick, so a chatty channel can't starve the others sharing the link.
+///
+/// The channel ids go from 0 to `N - 1`.
+pub struct QuotaMux<N, C>
+where
+    N: ArrayLength<ChannelQuota<C>> + Unsigned,
+{
+    quotas: GenericArray<ChannelQuota<C>, N>,
+}
+
+impl<C: Clock> ChannelQuota<C> {
+    /// Allows up to `max_in_flight` nodes in flight, with their bytes limited by `limiter`,
+    /// including the channel prefix.
+    pub fn new(limiter: RateLimiter<C>, max_in_flight: usize) -> Self {
+        Self {
+            limiter,
+            max_in_flight,
+            in_flight: 0,
+        }
+    }
+
+    /// Returns how many nodes of the channel were sent and not completed yet.
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    /// Returns the rate limiter of the channel, e.g. to read its counters.
+    #[inline]
+    pub fn limiter(&self) -> &RateLimiter<C> {
+        &self.limiter
+    }
+}
+
+impl<N, C> QuotaMux<N, C>
+where
+    N: ArrayLength<ChannelQuota<C>> + Unsigned,
+    C: Clock,
+{
+    /// Creates a mux calling `quota` for the limits of each channel.
+    ///
+    /// # Panics
+    ///
+    /// If `N` is bigger than 256, the channel ids are a byte.
+    pub fn new(mut quota: impl FnMut(u8) -> ChannelQuota<C>) -> Self {
+        assert!(N::USIZE <= 256, "too many channels");
+        Self {
+            quotas: crate::generate(|i| quota(i as u8)),
+        }
+    }
+
+    /// Writes `payload` prefixed with `channel` to `output` if the quotas of the channel allow
+    /// it, the node then counts as in flight until `complete` is called.
+    pub fn send(
+        &mut self,
+        channel: u8,
+        payload: &[u8],
+        output: &mut impl DMANodeMut<u8>,
+    ) -> Result<(), QuotaError> {
+        let quota = self
+            .quotas
+            .get_mut(usize::from(channel))
+            .ok_or(QuotaError::Channel)?;
+        if quota.in_flight >= quota.max_in_flight {
+            return Err(QuotaError::InFlight);
+        }
+        // Checked before taking the tokens, so a frame that doesn't fit doesn't use the rate
+        if payload.len() + 1 > output.free() {
+            return Err(QuotaError::Capacity);
+        }
+        if !quota.limiter.try_acquire(payload.len() + 1) {
+            return Err(QuotaError::Rate);
+        }
+
+        output.write_slice(&[channel]);
+        output.write_slice(payload);
+        quota.in_flight += 1;
+        Ok(())
+    }
+
+    /// Marks a node of `channel` as transferred, freeing its place in flight.
+    pub fn complete(&mut self, channel: u8) {
+        if let Some(quota) = self.quotas.get_mut(usize::from(channel)) {
+            quota.in_flight = quota.in_flight.saturating_sub(1);
+        }
+    }
+
+    /// Returns the quota of `channel`, `None` if it's out of range.
+    #[inline]
+    pub fn quota(&self, channel: u8) -> Option<&ChannelQuota<C>> {
+        self.quotas.get(usize::from(channel))
+    }
+}
+
+/// Splits a frame tagged by a `ChannelMux` into its channel and payload, `None` is returned if the
+/// frame is empty.
+#[inline]
+pub fn demux(frame: &[u8]) -> Option<(u8, &[u8])> {
+    frame
+        .split_first()
+        .map(|(&channel, payload)| (channel, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{demux, ChannelMux, ChannelQuota, QuotaError, QuotaMux};
+    use crate::{typenum::consts::*, CodecError, DMANodeMut, Node, RateLimiter, Stage};
+    use core::cell::Cell;
+
+    #[test]
+    fn mux_demux() {
+        let mut mux = ChannelMux::new(1);
+        let mut link = [Node::<U4, u8>::new(), Node::new()];
+        mux.process(b"abc", &mut link[0]).unwrap();
+        mux.set_channel(7);
+        mux.process(b"de", &mut link[1]).unwrap();
+        assert_eq!(
+            mux.process(b"fghi", &mut Node::<U4, u8>::new()),
+            Err(CodecError::Capacity)
+        );
+
+        assert_eq!(demux(&link[0]), Some((1, &b"abc"[..])));
+        assert_eq!(demux(&link[1]), Some((7, &b"de"[..])));
+        assert_eq!(demux(&[]), None);
+    }
+
+    #[test]
+    fn quotas() {
+        let time = Cell::new(0u32);
+        let clock = || time.get();
+        // Channel 0 is for control, channel 1 for a chatty debug output
+        let mut mux = QuotaMux::<U2, _>::new(|channel| match channel {
+            0 => ChannelQuota::new(RateLimiter::new(clock, 8, 1, 8), 1),
+            _ => ChannelQuota::new(RateLimiter::new(clock, 1, 1, 4), 4),
+        });
+        let mut node = Node::<U4, u8>::new();
+
+        mux.send(1, b"abc", &mut node).unwrap();
+        assert_eq!(&node[..], b"\x01abc");
+        node.clear();
+        assert_eq!(mux.send(1, b"d", &mut node), Err(QuotaError::Rate));
+        assert_eq!(mux.send(2, b"d", &mut node), Err(QuotaError::Channel));
+        assert_eq!(mux.send(0, b"defg", &mut node), Err(QuotaError::Capacity));
+        assert!(node.is_empty());
+
+        // The debug channel doesn't hold back the control one
+        mux.send(0, b"de", &mut node).unwrap();
+        node.clear();
+        assert_eq!(mux.send(0, b"f", &mut node), Err(QuotaError::InFlight));
+        mux.complete(0);
+        mux.send(0, b"f", &mut node).unwrap();
+
+        assert_eq!(mux.quota(0).unwrap().in_flight(), 1);
+        assert_eq!(mux.quota(1).unwrap().in_flight(), 1);
+        assert_eq!(mux.quota(1).unwrap().limiter().deferred(), 1);
+    }
+}
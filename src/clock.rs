@@ -0,0 +1,17 @@
+/// Source of timestamps for the time driven parts of the crate.
+///
+/// The ticks have an arbitrary frequency and are expected to wrap around at `u32::MAX`.
+pub trait Clock {
+    /// Returns the current time in ticks.
+    fn now(&self) -> u32;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> u32,
+{
+    #[inline]
+    fn now(&self) -> u32 {
+        self()
+    }
+}
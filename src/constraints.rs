@@ -0,0 +1,46 @@
+use core::ops::RangeInclusive;
+
+/// Describes the limitations of a DMA engine regarding the buffers it can use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DmaConstraints {
+    /// Required alignment of the buffer address, in bytes.
+    pub alignment: usize,
+    /// Addresses the DMA engine can access, the whole buffer must be inside this range.
+    pub address_range: RangeInclusive<usize>,
+    /// Maximum number of elements in a single transfer.
+    pub max_count: usize,
+}
+
+/// Reasons for a buffer to be rejected by `validate_for_dma`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaError {
+    /// The buffer address doesn't have the required alignment.
+    Misaligned,
+    /// Part of the buffer is outside of the addresses accessible by the DMA engine.
+    OutOfRange,
+    /// The number of elements is greater than the maximum for a single transfer.
+    TooLong,
+}
+
+impl DmaConstraints {
+    /// Checks a buffer starting at `address` with `size` bytes, of which `count` elements are
+    /// going to be transferred.
+    pub fn validate(&self, address: usize, size: usize, count: usize) -> Result<(), DmaError> {
+        if self.alignment != 0 && address % self.alignment != 0 {
+            return Err(DmaError::Misaligned);
+        }
+
+        let last = address
+            .checked_add(size.saturating_sub(1))
+            .ok_or(DmaError::OutOfRange)?;
+        if !self.address_range.contains(&address) || !self.address_range.contains(&last) {
+            return Err(DmaError::OutOfRange);
+        }
+
+        if count > self.max_count {
+            return Err(DmaError::TooLong);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{DMANodeMut, Node, NodeMut};
+use core::mem::MaybeUninit;
+use generic_array::{typenum::Unsigned, ArrayLength};
+
+/// Conversion into a `DMANodeMut`, so driver entry points can accept anything node-like with a
+/// single bound.
+pub trait IntoDmaNode<W> {
+    /// The node type given to the driver.
+    type Node: DMANodeMut<W>;
+
+    /// Performs the conversion.
+    fn into_dma_node(self) -> Self::Node;
+}
+
+impl<N, W> IntoDmaNode<W> for Node<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default,
+{
+    type Node = Self;
+
+    #[inline]
+    fn into_dma_node(self) -> Self::Node {
+        self
+    }
+}
+
+impl<'a, N, W> IntoDmaNode<W> for &'a mut Node<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default,
+{
+    type Node = NodeMut<'a, W>;
+
+    #[inline]
+    fn into_dma_node(self) -> Self::Node {
+        self.as_node_mut()
+    }
+}
+
+impl<'a, W: Default> IntoDmaNode<W> for NodeMut<'a, W> {
+    type Node = Self;
+
+    #[inline]
+    fn into_dma_node(self) -> Self::Node {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{prelude::*, typenum::consts::*};
+
+    fn load(node: impl IntoDmaNode<u8>) -> usize {
+        let mut node = node.into_dma_node();
+        node.write_slice(b"hello");
+        node.len()
+    }
+
+    #[test]
+    fn into_dma_node() {
+        let mut node = Node::<U8, u8>::new();
+        assert_eq!(load(&mut node), 5);
+        assert_eq!(load(node.as_node_mut()), 8);
+        assert_eq!(&node[..], b"hellohel");
+        assert_eq!(load(Node::<U4, u8>::new()), 4);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{DMANodeMut, Node};
+use core::mem::MaybeUninit;
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+/// Filter that detects when a node has the same contents as the previous one, so periodic frames
+/// that didn't change don't need to be sent.
+///
+/// It keeps a copy of the last contents in a node of capacity `N`, contents bigger than that
+/// can't be stored and are never considered duplicated.
+pub struct Dedup<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+{
+    last: Node<N, W>,
+    valid: bool,
+    dropped: u32,
+}
+
+impl<N, W> Dedup<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default + Copy + PartialEq,
+{
+    /// Creates a new filter, the first contents checked are never duplicated.
+    pub fn new() -> Self {
+        Self {
+            last: Node::new(),
+            valid: false,
+            dropped: 0,
+        }
+    }
+
+    /// Checks if `data` is equal to the contents of the previous call, in which case it should be
+    /// dropped, otherwise it's stored for the next comparison.
+    pub fn is_duplicate(&mut self, data: &[W]) -> bool {
+        if self.valid && self.last[..] == *data {
+            self.dropped = self.dropped.wrapping_add(1);
+            return true;
+        }
+
+        self.last.clear();
+        self.valid = self.last.write_slice(data) == data.len();
+        false
+    }
+
+    /// Forgets the stored contents, so the next ones are always sent, e.g. after the link is
+    /// re-established.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.valid = false;
+    }
+
+    /// Returns how many duplicated contents were detected.
+    #[inline]
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+impl<N, W> Default for Dedup<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default + Copy + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dedup;
+    use crate::typenum::consts::*;
+
+    #[test]
+    fn drops_repeated() {
+        let mut dedup = Dedup::<U4, u8>::new();
+        assert!(!dedup.is_duplicate(&[1, 2]));
+        assert!(dedup.is_duplicate(&[1, 2]));
+        assert!(!dedup.is_duplicate(&[1, 3]));
+        assert!(!dedup.is_duplicate(&[1, 2]));
+
+        // Too big to be stored
+        assert!(!dedup.is_duplicate(&[0; 5]));
+        assert!(!dedup.is_duplicate(&[0; 5]));
+
+        dedup.is_duplicate(&[]);
+        dedup.reset();
+        assert!(!dedup.is_duplicate(&[]));
+        assert_eq!(dedup.dropped(), 1);
+    }
+}
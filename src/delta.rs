@@ -0,0 +1,210 @@
+use crate::{CodecError, DMANodeMut, DMANodeRef, Node};
+use core::mem::MaybeUninit;
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+const KEY: u8 = 0;
+const DELTA: u8 = 1;
+
+/// Encodes periodic frames as the XOR against a reference frame, so the parts that didn't change
+/// become zeros, which compress well, e.g. with `rle_compress`.
+///
+/// Each encoded frame starts with a tag byte, key frames carry the raw contents and become the
+/// new reference, delta frames carry the XOR against the reference. A key frame is sent first,
+/// whenever the frame length changes and after `reset`.
+pub struct DeltaEncoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    reference: Node<N, u8>,
+    valid: bool,
+}
+
+/// Decodes the frames produced by a `DeltaEncoder`.
+pub struct DeltaDecoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    reference: Node<N, u8>,
+    valid: bool,
+}
+
+impl<N> DeltaEncoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    /// Creates a new encoder without reference, so the first frame is a key frame.
+    pub fn new() -> Self {
+        Self {
+            reference: Node::new(),
+            valid: false,
+        }
+    }
+
+    /// Encodes `src` into `dest`, on error `dest` is left as it was and the reference isn't
+    /// changed. Frames bigger than the reference node are rejected as `Malformed`.
+    pub fn encode(&mut self, src: &[u8], dest: &mut impl DMANodeMut<u8>) -> Result<(), CodecError> {
+        if src.len() > self.reference.max_len() {
+            return Err(CodecError::Malformed);
+        }
+        if src.len() + 1 > dest.free() {
+            return Err(CodecError::Capacity);
+        }
+
+        if self.valid && self.reference.len() == src.len() {
+            dest.write_slice(&[DELTA]);
+            for (byte, reference) in src.iter().zip(self.reference.iter()) {
+                dest.write_slice(&[byte ^ reference]);
+            }
+        } else {
+            dest.write_slice(&[KEY]);
+            dest.write_slice(src);
+            self.reference.clear();
+            self.reference.write_slice(src);
+            self.valid = true;
+        }
+        Ok(())
+    }
+
+    /// Makes the next frame a key frame, e.g. periodically so a receiver that lost the previous
+    /// one can resynchronize.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.valid = false;
+    }
+}
+
+impl<N> DeltaDecoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    /// Creates a new decoder waiting for a key frame.
+    pub fn new() -> Self {
+        Self {
+            reference: Node::new(),
+            valid: false,
+        }
+    }
+
+    /// Decodes `src` into `dest`, on error `dest` is left as it was. A delta frame is `Malformed`
+    /// if no key frame with the same length was received. A key frame becomes the reference even
+    /// if it's rejected as `Capacity`, to stay in sync with the encoder.
+    pub fn decode(&mut self, src: &[u8], dest: &mut impl DMANodeMut<u8>) -> Result<(), CodecError> {
+        let (&tag, data) = src.split_first().ok_or(CodecError::Malformed)?;
+        let valid_delta = self.valid && self.reference.len() == data.len();
+        if (tag == DELTA && !valid_delta)
+            || (tag == KEY && data.len() > self.reference.max_len())
+            || tag > DELTA
+        {
+            return Err(CodecError::Malformed);
+        }
+        // The encoder already took a key frame as its reference, so adopt it even if it doesn't
+        // fit `dest`, otherwise the following deltas would decode against a stale reference
+        if tag == KEY {
+            self.reference.clear();
+            self.reference.write_slice(data);
+            self.valid = true;
+        }
+        if data.len() > dest.free() {
+            return Err(CodecError::Capacity);
+        }
+
+        if tag == DELTA {
+            for (byte, reference) in data.iter().zip(self.reference.iter()) {
+                dest.write_slice(&[byte ^ reference]);
+            }
+        } else {
+            dest.write_slice(data);
+        }
+        Ok(())
+    }
+}
+
+impl<N> Default for DeltaEncoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> Default for DeltaDecoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeltaDecoder, DeltaEncoder};
+    use crate::{typenum::consts::*, CodecError, DMANodeMut, DMANodeRef, Node};
+
+    #[test]
+    fn round_trip() {
+        let mut encoder = DeltaEncoder::<U4>::new();
+        let mut decoder = DeltaDecoder::<U4>::new();
+        let mut encoded = Node::<U8, u8>::new();
+        let mut decoded = Node::<U4, u8>::new();
+
+        for (frame, expected) in [
+            (&[1u8, 2, 3][..], &[0u8, 1, 2, 3][..]),
+            (&[1, 2, 4], &[1, 0, 0, 7]),
+            (&[1, 2], &[0, 1, 2]),
+        ]
+        .iter()
+        {
+            encoded.clear();
+            decoded.clear();
+            encoder.encode(frame, &mut encoded).unwrap();
+            assert_eq!(&encoded[..], *expected);
+            decoder.decode(&encoded, &mut decoded).unwrap();
+            assert_eq!(&decoded[..], *frame);
+        }
+
+        encoder.reset();
+        encoded.clear();
+        encoder.encode(&[1, 2], &mut encoded).unwrap();
+        assert_eq!(encoded[0], 0);
+    }
+
+    #[test]
+    fn errors() {
+        let mut decoder = DeltaDecoder::<U4>::new();
+        let mut decoded = Node::<U4, u8>::new();
+        assert_eq!(
+            decoder.decode(&[1, 0, 0], &mut decoded),
+            Err(CodecError::Malformed)
+        );
+        assert_eq!(
+            decoder.decode(&[], &mut decoded),
+            Err(CodecError::Malformed)
+        );
+        assert_eq!(
+            decoder.decode(&[0; 6], &mut decoded),
+            Err(CodecError::Malformed)
+        );
+
+        // A key frame rejected for capacity still replaces the reference
+        let mut small = Node::<U2, u8>::new();
+        decoder.decode(&[0, 1, 1, 1, 1], &mut decoded).unwrap();
+        assert_eq!(
+            decoder.decode(&[0, 9, 9, 9, 9], &mut small),
+            Err(CodecError::Capacity)
+        );
+        assert!(small.is_empty());
+        decoded.clear();
+        decoder.decode(&[1, 0, 0, 0, 1], &mut decoded).unwrap();
+        assert_eq!(&decoded[..], &[9, 9, 9, 8]);
+
+        let mut encoder = DeltaEncoder::<U4>::new();
+        let mut encoded = Node::<U4, u8>::new();
+        assert_eq!(
+            encoder.encode(&[0; 4], &mut encoded),
+            Err(CodecError::Capacity)
+        );
+        assert!(encoded.is_empty());
+    }
+}
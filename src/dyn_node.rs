@@ -0,0 +1,185 @@
+use crate::{DMANodeMut, DMANodeRef, IntoDmaNode, NodeMut, NodeRef};
+use core::{
+    fmt,
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+/// Node whose capacity is given by the buffer provided at construction instead of its type, for
+/// when the capacity is only known at runtime.
+pub struct DynNode<'a, W> {
+    buf: &'a mut [MaybeUninit<W>],
+    len: usize,
+}
+
+impl<'a, W> DynNode<'a, W> {
+    /// Creates an empty node using the whole `buf` as its buffer.
+    pub fn new(buf: &'a mut [MaybeUninit<W>]) -> Self {
+        #[allow(unused_mut)]
+        let mut node = Self { buf, len: 0 };
+        #[cfg(feature = "poison")]
+        node.as_node_mut().poison();
+        node
+    }
+
+    /// Borrows the node as a `NodeRef`.
+    #[inline]
+    pub fn as_node_ref(&self) -> NodeRef<'_, W> {
+        NodeRef::new(self.buf, self.len)
+    }
+
+    /// Borrows the node as a `NodeMut`.
+    #[inline]
+    pub fn as_node_mut(&mut self) -> NodeMut<'_, W> {
+        NodeMut::new(self.buf, &mut self.len)
+    }
+}
+
+impl<W> DMANodeRef<W> for DynNode<'_, W> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    fn buffer_address_for_dma(&self) -> usize {
+        self.buf.as_ptr() as usize
+    }
+
+    #[inline]
+    fn max_len(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+impl<W: Default> DMANodeMut<W> for DynNode<'_, W> {
+    #[inline]
+    fn write(&mut self) -> &mut [W] {
+        let mut node = self.as_node_mut();
+        node.write();
+        node.into_slice()
+    }
+
+    #[inline]
+    fn commit(&mut self, shrink_to: usize) {
+        self.as_node_mut().commit(shrink_to)
+    }
+
+    #[inline]
+    fn write_slice(&mut self, buf: &[W]) -> usize {
+        self.as_node_mut().write_slice(buf)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.as_node_mut().clear()
+    }
+
+    #[inline]
+    unsafe fn set_len(&mut self, len: usize) {
+        self.as_node_mut().set_len(len)
+    }
+}
+
+impl<W: Default> IntoDmaNode<W> for DynNode<'_, W> {
+    type Node = Self;
+
+    #[inline]
+    fn into_dma_node(self) -> Self::Node {
+        self
+    }
+}
+
+impl<'a, W: Default> IntoDmaNode<W> for &'a mut DynNode<'_, W> {
+    type Node = NodeMut<'a, W>;
+
+    #[inline]
+    fn into_dma_node(self) -> Self::Node {
+        self.as_node_mut()
+    }
+}
+
+impl<W> Deref for DynNode<'_, W> {
+    type Target = [W];
+
+    fn deref(&self) -> &Self::Target {
+        // Safe as it uses the internal length of valid data
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const _, self.len) }
+    }
+}
+
+impl<W> DerefMut for DynNode<'_, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safe as it uses the internal length of valid data
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut _, self.len) }
+    }
+}
+
+impl<W> Drop for DynNode<'_, W> {
+    fn drop(&mut self) {
+        for elem in self.iter_mut() {
+            unsafe {
+                ptr::drop_in_place(elem);
+            }
+        }
+    }
+}
+
+impl<W: fmt::Debug> fmt::Debug for DynNode<'_, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", &self[..])
+    }
+}
+
+impl<'a> Extend<&'a str> for DynNode<'_, u8> {
+    /// Appends the strings until the node is full, the last one may be truncated, always on a
+    /// `char` boundary so the node stays valid UTF-8.
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        crate::format::extend_str(self, iter)
+    }
+}
+
+impl fmt::Write for DynNode<'_, u8> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        crate::push_str(self, s).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DMANodeMut, DMANodeRef, DynNode};
+    use core::{fmt::Write, mem::MaybeUninit};
+
+    #[test]
+    fn dyn_node() {
+        let mut buf = [MaybeUninit::<u8>::uninit(); 6];
+        let mut node = DynNode::new(&mut buf[..]);
+        assert_eq!((node.len(), node.max_len()), (0, 6));
+        assert_eq!(node.write_slice(b"hello world"), 6);
+        node.commit(5);
+        assert_eq!(&node[..], b"hello");
+        assert_eq!(node.as_node_ref().len(), 5);
+
+        node.as_node_mut().write_slice(b"!");
+        assert_eq!(&node[..], b"hello!");
+        node.clear();
+        assert!(node.is_empty());
+        assert_eq!(node.write().len(), 6);
+    }
+
+    #[test]
+    fn text() {
+        let mut buf = [MaybeUninit::<u8>::uninit(); 7];
+        let mut node = DynNode::new(&mut buf[..]);
+        crate::push_str(&mut node, "42").unwrap();
+        write!(node, "{:x}", 15).unwrap();
+        node.extend(["yz", "lá"].iter().copied());
+        assert_eq!(core::str::from_utf8(&node), Ok("42fyzl"));
+    }
+}
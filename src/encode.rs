@@ -0,0 +1,88 @@
+use crate::{BitOrder, CapacityError, DMANodeMut};
+
+/// Symbols emitted by `encode_bits` for each bit value, e.g. GPIO levels, timer compare values or
+/// `BSRR` words that the DMA engine writes to the peripheral.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SymbolTable<'a, S> {
+    /// Symbols for a `0` bit.
+    pub zero: &'a [S],
+    /// Symbols for a `1` bit.
+    pub one: &'a [S],
+}
+
+/// NRZ levels, a single symbol with the bit value.
+pub const NRZ: SymbolTable<'static, u8> = SymbolTable {
+    zero: &[0],
+    one: &[1],
+};
+
+/// Manchester levels as in IEEE 802.3, a `0` is a high to low transition and a `1` is a low to
+/// high transition.
+pub const MANCHESTER: SymbolTable<'static, u8> = SymbolTable {
+    zero: &[1, 0],
+    one: &[0, 1],
+};
+
+/// Expands every bit of `src` into its symbols from `table`, appending them to `dest`, e.g. for
+/// waveforms generated by DMA writing to GPIO or timer registers. Nothing is written if all the
+/// symbols don't fit.
+pub fn encode_bits<S: Copy>(
+    src: &[u8],
+    dest: &mut impl DMANodeMut<S>,
+    table: &SymbolTable<'_, S>,
+    order: BitOrder,
+) -> Result<(), CapacityError> {
+    let ones = src
+        .iter()
+        .map(|byte| byte.count_ones() as usize)
+        .sum::<usize>();
+    let zeros = src.len() * 8 - ones;
+    if ones * table.one.len() + zeros * table.zero.len() > dest.free() {
+        return Err(CapacityError);
+    }
+
+    for byte in src {
+        for i in 0..8 {
+            let bit = match order {
+                BitOrder::MsbFirst => byte >> (7 - i),
+                BitOrder::LsbFirst => byte >> i,
+            } & 1;
+            dest.write_slice(if bit == 1 { table.one } else { table.zero });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_bits, SymbolTable, MANCHESTER, NRZ};
+    use crate::{typenum::consts::*, BitOrder, CapacityError, DMANodeMut, Node};
+
+    #[test]
+    fn manchester_nrz() {
+        let mut node = Node::<U16, u8>::new();
+        encode_bits(&[0b1000_0001], &mut node, &NRZ, BitOrder::LsbFirst).unwrap();
+        assert_eq!(&node[..], &[1, 0, 0, 0, 0, 0, 0, 1]);
+
+        node.clear();
+        encode_bits(&[0b1100_0000], &mut node, &MANCHESTER, BitOrder::MsbFirst).unwrap();
+        assert_eq!(&node[..4], &[0, 1, 0, 1]);
+        assert_eq!(&node[4..], &[1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0]);
+        assert_eq!(
+            encode_bits(&[0], &mut node, &NRZ, BitOrder::MsbFirst),
+            Err(CapacityError)
+        );
+    }
+
+    #[test]
+    fn custom_table() {
+        // Timer compare values for a pulse width encoding
+        let table = SymbolTable {
+            zero: &[10u16],
+            one: &[20, 20],
+        };
+        let mut node = Node::<U16, u16>::new();
+        encode_bits(&[0x0F], &mut node, &table, BitOrder::MsbFirst).unwrap();
+        assert_eq!(&node[..], &[10, 10, 10, 10, 20, 20, 20, 20, 20, 20, 20, 20]);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{DMANodeMut, DMANodeRef};
+use core::{
+    fmt,
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+/// Shared borrow of a node that doesn't carry its capacity in the type, so driver functions
+/// don't need to be generic over it.
+pub struct NodeRef<'a, W> {
+    buf: &'a [MaybeUninit<W>],
+    len: usize,
+}
+
+/// Mutable borrow of a node that doesn't carry its capacity in the type, so driver functions
+/// don't need to be generic over it. Changes to the length are reflected in the borrowed node.
+pub struct NodeMut<'a, W> {
+    buf: &'a mut [MaybeUninit<W>],
+    len: &'a mut usize,
+}
+
+impl<'a, W> NodeRef<'a, W> {
+    pub(crate) fn new(buf: &'a [MaybeUninit<W>], len: usize) -> Self {
+        Self { buf, len }
+    }
+}
+
+impl<W> DMANodeRef<W> for NodeRef<'_, W> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    fn buffer_address_for_dma(&self) -> usize {
+        self.buf.as_ptr() as usize
+    }
+
+    #[inline]
+    fn max_len(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+impl<W> Deref for NodeRef<'_, W> {
+    type Target = [W];
+
+    fn deref(&self) -> &Self::Target {
+        // Safe as it uses the length of valid data of the borrowed node
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const _, self.len) }
+    }
+}
+
+impl<'a, W> NodeMut<'a, W> {
+    pub(crate) fn new(buf: &'a mut [MaybeUninit<W>], len: &'a mut usize) -> Self {
+        Self { buf, len }
+    }
+
+    /// Converts the view into the valid elements of the borrowed node.
+    #[inline]
+    pub fn into_slice(self) -> &'a mut [W] {
+        // Safe as it uses the length of valid data of the borrowed node
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut _, *self.len) }
+    }
+
+    /// Fills the unused part of the buffer with `POISON`.
+    #[cfg(feature = "poison")]
+    pub(crate) fn poison(&mut self) {
+        // Safe as it only touches elements past the initialized length
+        unsafe {
+            ptr::write_bytes(
+                self.buf.as_mut_ptr().add(*self.len),
+                crate::POISON,
+                self.buf.len() - *self.len,
+            );
+        }
+    }
+}
+
+impl<W> Deref for NodeMut<'_, W> {
+    type Target = [W];
+
+    fn deref(&self) -> &Self::Target {
+        // Safe as it uses the length of valid data of the borrowed node
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const _, *self.len) }
+    }
+}
+
+impl<W> DerefMut for NodeMut<'_, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safe as it uses the length of valid data of the borrowed node
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut _, *self.len) }
+    }
+}
+
+impl<W> DMANodeRef<W> for NodeMut<'_, W> {
+    #[inline]
+    fn len(&self) -> usize {
+        *self.len
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        *self.len == 0
+    }
+
+    #[inline]
+    fn buffer_address_for_dma(&self) -> usize {
+        self.buf.as_ptr() as usize
+    }
+
+    #[inline]
+    fn max_len(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+impl<W: Default> DMANodeMut<W> for NodeMut<'_, W> {
+    fn write(&mut self) -> &mut [W] {
+        // Initialize memory with a safe value
+        for elem in self.buf.iter_mut().skip(*self.len) {
+            unsafe {
+                ptr::write(elem.as_mut_ptr(), W::default());
+            }
+        }
+        *self.len = self.buf.len(); // Set to max so `commit` may shrink it if needed
+
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut _, self.buf.len()) }
+    }
+
+    fn commit(&mut self, shrink_to: usize) {
+        // Only shrinking is allowed to remain safe with the `MaybeUninit`
+        if shrink_to < *self.len {
+            *self.len = shrink_to;
+        }
+    }
+
+    fn write_slice(&mut self, buf: &[W]) -> usize {
+        let count = buf.len().min(self.free());
+
+        // Used to write data into the `MaybeUninit`, safe based on the size check above
+        unsafe {
+            ptr::copy_nonoverlapping(
+                buf.as_ptr(),
+                self.buf.as_mut_ptr().add(*self.len) as *mut W,
+                count,
+            );
+        }
+
+        *self.len += count;
+        count
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        *self.len = 0;
+        #[cfg(feature = "poison")]
+        self.poison();
+    }
+
+    #[inline]
+    unsafe fn set_len(&mut self, len: usize) {
+        *self.len = len;
+    }
+}
+
+impl<'a> Extend<&'a str> for NodeMut<'_, u8> {
+    /// Appends the strings until the node is full, the last one may be truncated, always on a
+    /// `char` boundary so the node stays valid UTF-8.
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        crate::format::extend_str(self, iter)
+    }
+}
+
+impl fmt::Write for NodeMut<'_, u8> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        crate::push_str(self, s).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NodeMut, NodeRef};
+    use crate::{typenum::consts::*, DMANodeMut, DMANodeRef, Node};
+
+    fn fill(mut node: NodeMut<'_, u8>) -> usize {
+        let written = node.write_slice(&[1, 2, 3, 4, 5, 6]);
+        node.commit(written - 1);
+        node.max_len()
+    }
+
+    fn sum(node: NodeRef<'_, u8>) -> u8 {
+        node.iter().sum()
+    }
+
+    #[test]
+    fn erased() {
+        let mut small = Node::<U4, u8>::new();
+        let mut big = Node::<U8, u8>::new();
+        assert_eq!(fill(small.as_node_mut()), 4);
+        assert_eq!(fill(big.as_node_mut()), 8);
+        assert_eq!(&small[..], &[1, 2, 3]);
+        assert_eq!(&big[..], &[1, 2, 3, 4, 5]);
+
+        let node = small.as_node_ref();
+        assert_eq!(
+            node.buffer_address_for_dma(),
+            small.buffer_address_for_dma()
+        );
+        assert_eq!((node.len(), node.max_len()), (3, 4));
+        assert_eq!(sum(big.as_node_ref()), 15);
+
+        let mut node = big.as_node_mut();
+        node.clear();
+        assert!(node.write().iter().all(|elem| *elem == 0));
+        assert_eq!(big.len(), 8);
+    }
+}
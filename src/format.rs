@@ -0,0 +1,174 @@
+use crate::{CapacityError, DMANodeMut, Node};
+use core::mem::MaybeUninit;
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Enough for a sign, 10 digits and a dot after a leading zero.
+type Digits = [u8; 13];
+
+/// Appends `s` to `node`, nothing is written if there isn't enough free space for all of it.
+#[inline]
+pub fn push_str(node: &mut impl DMANodeMut<u8>, s: &str) -> Result<(), CapacityError> {
+    node.try_write_vectored(&[s.as_bytes()])
+}
+
+/// Appends the strings until `node` is full, the last one may be truncated, always on a `char`
+/// boundary so the node stays valid UTF-8.
+pub(crate) fn extend_str<'a>(
+    node: &mut impl DMANodeMut<u8>,
+    iter: impl IntoIterator<Item = &'a str>,
+) {
+    for s in iter {
+        let mut end = s.len().min(node.free());
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        node.write_slice(&s.as_bytes()[..end]);
+        if end < s.len() {
+            break;
+        }
+    }
+}
+
+/// Appends `value` to `node` in decimal.
+///
+/// The numeric helpers write directly into the free space of the node, without the code size of
+/// `core::fmt`. Nothing is written if the whole number doesn't fit.
+pub fn push_u32_dec(node: &mut impl DMANodeMut<u8>, value: u32) -> Result<(), CapacityError> {
+    let mut buf = Digits::default();
+    let start = write_digits(&mut buf, value, 10, 0);
+    node.try_write_vectored(&[&buf[start..]])
+}
+
+/// Appends `value` to `node` in decimal, with a `-` if negative, see `push_u32_dec`.
+#[inline]
+pub fn push_i32_dec(node: &mut impl DMANodeMut<u8>, value: i32) -> Result<(), CapacityError> {
+    push_fixed_dec(node, value, 0)
+}
+
+/// Appends `value` to `node` in uppercase hexadecimal, without prefix or leading zeros, see
+/// `push_u32_dec`.
+pub fn push_u32_hex(node: &mut impl DMANodeMut<u8>, value: u32) -> Result<(), CapacityError> {
+    let mut buf = Digits::default();
+    let start = write_digits(&mut buf, value, 16, 0);
+    node.try_write_vectored(&[&buf[start..]])
+}
+
+/// Appends the fixed-point `value` to `node` with `decimals` digits after the dot, e.g. `-1234`
+/// with 2 decimals is written as `-12.34` and `5` with 3 decimals as `0.005`, see `push_u32_dec`.
+///
+/// # Panics
+///
+/// If `decimals` is greater than 10.
+pub fn push_fixed_dec(
+    node: &mut impl DMANodeMut<u8>,
+    value: i32,
+    decimals: usize,
+) -> Result<(), CapacityError> {
+    assert!(decimals <= 10);
+    let mut buf = Digits::default();
+    let mut start = write_digits(&mut buf, value.unsigned_abs(), 10, decimals);
+    if value < 0 {
+        start -= 1;
+        buf[start] = b'-';
+    }
+    node.try_write_vectored(&[&buf[start..]])
+}
+
+/// Numeric formatting helpers, like the free functions with the same names.
+impl<N> Node<N, u8>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    /// Appends `value` in decimal.
+    #[inline]
+    pub fn push_u32_dec(&mut self, value: u32) -> Result<(), CapacityError> {
+        push_u32_dec(self, value)
+    }
+
+    /// Appends `value` in decimal, with a `-` if negative.
+    #[inline]
+    pub fn push_i32_dec(&mut self, value: i32) -> Result<(), CapacityError> {
+        push_i32_dec(self, value)
+    }
+
+    /// Appends `value` in uppercase hexadecimal, without prefix or leading zeros.
+    #[inline]
+    pub fn push_u32_hex(&mut self, value: u32) -> Result<(), CapacityError> {
+        push_u32_hex(self, value)
+    }
+
+    /// Appends the fixed-point `value` with `decimals` digits after the dot, see the free
+    /// function `push_fixed_dec`.
+    #[inline]
+    pub fn push_fixed_dec(&mut self, value: i32, decimals: usize) -> Result<(), CapacityError> {
+        push_fixed_dec(self, value, decimals)
+    }
+}
+
+/// Writes the digits of `value` at the end of `buf`, with at least one integer digit and a dot
+/// before the last `decimals` digits, and returns where they start.
+fn write_digits(buf: &mut Digits, mut value: u32, radix: u32, decimals: usize) -> usize {
+    let mut start = buf.len() - 1;
+    let mut count = 0;
+    while value != 0 || count <= decimals {
+        if count == decimals && decimals != 0 {
+            buf[start] = b'.';
+            start -= 1;
+        }
+        buf[start] = DIGITS[(value % radix) as usize];
+        value /= radix;
+        count += 1;
+        start -= 1;
+    }
+    start + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{push_str, push_u32_hex};
+    use crate::{typenum::consts::*, CapacityError, DMANodeMut, DMANodeRef, Node};
+
+    #[test]
+    fn integers() {
+        let mut node = Node::<U32, u8>::new();
+        node.push_u32_dec(0).unwrap();
+        node.push_str(" ").unwrap();
+        node.push_u32_dec(u32::MAX).unwrap();
+        node.push_str(" ").unwrap();
+        node.push_i32_dec(i32::MIN).unwrap();
+        node.push_str(" ").unwrap();
+        node.push_u32_hex(0xBEEF).unwrap();
+        assert_eq!(&node[..], b"0 4294967295 -2147483648 BEEF");
+        assert_eq!(node.push_u32_dec(1234), Err(CapacityError));
+        assert_eq!(node.len(), 29);
+    }
+
+    #[test]
+    fn fixed_point() {
+        let mut node = Node::<U32, u8>::new();
+        node.push_fixed_dec(-1234, 2).unwrap();
+        node.push_str(" ").unwrap();
+        node.push_fixed_dec(5, 3).unwrap();
+        node.push_str(" ").unwrap();
+        node.push_fixed_dec(i32::MIN, 10).unwrap();
+        assert_eq!(&node[..], b"-12.34 0.005 -0.2147483648");
+    }
+
+    #[test]
+    fn node_mut() {
+        let mut node = Node::<U8, u8>::new();
+        let mut erased = node.as_node_mut();
+        push_str(&mut erased, "0x").unwrap();
+        assert_eq!(push_str(&mut erased, "0123456"), Err(CapacityError));
+        erased.extend(["ab", "cdefg"].iter().copied());
+        assert_eq!(&node[..], b"0xabcdef");
+
+        node.clear();
+        let mut erased = node.as_node_mut();
+        push_u32_hex(&mut erased, 0xCAFE).unwrap();
+        assert_eq!(push_u32_hex(&mut erased, 0xBEEF0), Err(CapacityError));
+        assert_eq!(&node[..], b"CAFE");
+    }
+}
@@ -0,0 +1,149 @@
+use crate::Node;
+use core::mem::MaybeUninit;
+use embedded_hal::{serial, spi};
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+/// Non-blocking drain of a node through a serial, for peripherals without DMA.
+///
+/// It keeps track of how many words were already sent, so `poll` can be called again after
+/// `WouldBlock` until the whole node is written.
+#[derive(Debug, Default)]
+pub struct Drain {
+    sent: usize,
+}
+
+impl Drain {
+    /// Creates a drain starting at the beginning of the node.
+    pub fn new() -> Self {
+        Self { sent: 0 }
+    }
+
+    /// Writes as many words from `node` as the serial accepts, returns `Ok` once all of them were
+    /// written and flushed.
+    pub fn poll<N, W, S>(&mut self, node: &Node<N, W>, serial: &mut S) -> nb::Result<(), S::Error>
+    where
+        N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+        W: Copy,
+        S: serial::Write<W> + ?Sized,
+    {
+        while let Some(word) = node.get(self.sent) {
+            serial.write(*word)?;
+            self.sent += 1;
+        }
+        serial.flush()
+    }
+
+    /// Returns how many words were already written.
+    #[inline]
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+}
+
+impl<N, W> Node<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Copy,
+{
+    /// Writes the whole node through a serial, blocking until it's flushed.
+    pub fn drain_to_serial<S>(&self, serial: &mut S) -> Result<(), S::Error>
+    where
+        S: serial::Write<W> + ?Sized,
+    {
+        let mut drain = Drain::new();
+        nb::block!(drain.poll(self, serial))
+    }
+
+    /// Sends the whole node through a SPI word by word, blocking until done, the words read back
+    /// are discarded.
+    pub fn drain_to_spi<S>(&self, spi: &mut S) -> Result<(), S::Error>
+    where
+        S: spi::FullDuplex<W> + ?Sized,
+    {
+        for word in self.iter() {
+            nb::block!(spi.send(*word))?;
+            nb::block!(spi.read())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Drain;
+    use crate::{typenum::consts::*, DMANodeMut, Node};
+    use embedded_hal::{serial, spi};
+
+    /// Accepts a word every other call.
+    #[derive(Default)]
+    struct Port {
+        out: [u8; 8],
+        len: usize,
+        busy: bool,
+    }
+
+    impl Port {
+        fn ready(&mut self) -> nb::Result<(), ()> {
+            self.busy = !self.busy;
+            if self.busy {
+                Err(nb::Error::WouldBlock)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl serial::Write<u8> for Port {
+        type Error = ();
+
+        fn write(&mut self, word: u8) -> nb::Result<(), ()> {
+            self.ready()?;
+            self.out[self.len] = word;
+            self.len += 1;
+            Ok(())
+        }
+
+        fn flush(&mut self) -> nb::Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    impl spi::FullDuplex<u8> for Port {
+        type Error = ();
+
+        fn read(&mut self) -> nb::Result<u8, ()> {
+            self.ready()?;
+            Ok(0)
+        }
+
+        fn send(&mut self, word: u8) -> nb::Result<(), ()> {
+            serial::Write::write(self, word)
+        }
+    }
+
+    #[test]
+    fn drain_nb() {
+        let mut node = Node::<U4, u8>::new();
+        node.write_slice(&[1, 2, 3]);
+        let mut port = Port::default();
+        let mut drain = Drain::new();
+
+        assert_eq!(drain.poll(&node, &mut port), Err(nb::Error::WouldBlock));
+        assert_eq!(drain.poll(&node, &mut port), Err(nb::Error::WouldBlock));
+        assert_eq!(drain.sent(), 1);
+        assert_eq!(nb::block!(drain.poll(&node, &mut port)), Ok(()));
+        assert_eq!(&port.out[..port.len], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn drain_blocking() {
+        let mut node = Node::<U4, u8>::new();
+        node.write_slice(&[1, 2, 3]);
+        let mut port = Port::default();
+
+        let serial: &mut dyn serial::Write<u8, Error = ()> = &mut port;
+        node.drain_to_serial(serial).unwrap();
+        node.drain_to_spi(&mut port).unwrap();
+        assert_eq!(&port.out[..port.len], &[1, 2, 3, 1, 2, 3]);
+    }
+}
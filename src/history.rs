@@ -0,0 +1,189 @@
+use crate::{DMANodeMut, DMANodeRef, Node};
+use core::mem::MaybeUninit;
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength, GenericArray};
+
+/// Copy of a node's contents taken by `History::record`.
+pub struct HistoryRecord<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+{
+    timestamp: u32,
+    node: Node<N, W>,
+}
+
+/// Diagnostic ring keeping copies of the last `D` completed nodes with their timestamps, so they
+/// can be dumped after a fault.
+pub struct History<D, N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    D: ArrayLength<HistoryRecord<N, W>> + Unsigned,
+{
+    records: GenericArray<HistoryRecord<N, W>, D>,
+    next: usize,
+    len: usize,
+}
+
+impl<N, W> HistoryRecord<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+{
+    /// Returns the timestamp given to `History::record`.
+    #[inline]
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// Returns the copy of the node.
+    #[inline]
+    pub fn node(&self) -> &Node<N, W> {
+        &self.node
+    }
+}
+
+impl<D, N, W> History<D, N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    D: ArrayLength<HistoryRecord<N, W>> + Unsigned,
+    W: Default + Copy,
+{
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Self {
+            records: crate::generate(|_| HistoryRecord {
+                timestamp: 0,
+                node: Node::new(),
+            }),
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Stores a copy of `data` with its `timestamp`, overwriting the oldest record if the history
+    /// is full. Data that doesn't fit in a node is truncated.
+    pub fn record(&mut self, timestamp: u32, data: &[W]) {
+        let record = &mut self.records[self.next];
+        record.timestamp = timestamp;
+        record.node.clear();
+        record.node.write_slice(data);
+
+        self.next = (self.next + 1) % D::USIZE;
+        self.len = (self.len + 1).min(D::USIZE);
+    }
+
+    /// Iterates over the records from the oldest to the newest.
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryRecord<N, W>> {
+        let start = (self.next + D::USIZE - self.len) % D::USIZE;
+        (0..self.len).map(move |i| &self.records[(start + i) % D::USIZE])
+    }
+
+    /// Returns the number of stored records.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if there are no records.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all records.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+}
+
+impl<D, N> History<D, N, u8>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+    D: ArrayLength<HistoryRecord<N, u8>> + Unsigned,
+{
+    /// Emits a binary snapshot of the history through `out`, suitable to be stored in backup RAM
+    /// or a crash log.
+    ///
+    /// All the fields are little endian, the header is the capacity, the number of records and
+    /// the index of the next record to be written, each as `u16`. Then every record follows, from
+    /// the oldest to the newest, as its `u32` timestamp, its `u16` length and its bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity of the history or of its nodes doesn't fit in a `u16`.
+    pub fn dump_state(&self, out: &mut impl FnMut(&[u8])) {
+        assert!(
+            D::USIZE <= u16::MAX as usize && N::USIZE <= u16::MAX as usize,
+            "history too large for the dump format"
+        );
+        out(&(D::USIZE as u16).to_le_bytes());
+        out(&(self.len as u16).to_le_bytes());
+        out(&(self.next as u16).to_le_bytes());
+
+        for record in self.iter() {
+            out(&record.timestamp.to_le_bytes());
+            out(&(record.node.len() as u16).to_le_bytes());
+            out(&record.node);
+        }
+    }
+}
+
+impl<D, N, W> Default for History<D, N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    D: ArrayLength<HistoryRecord<N, W>> + Unsigned,
+    W: Default + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+    use crate::typenum::consts::*;
+
+    #[test]
+    fn keeps_last_records() {
+        let mut history = History::<U3, U4, u8>::new();
+        assert!(history.is_empty());
+
+        for i in 0..5u8 {
+            history.record(u32::from(i) * 10, &[i; 6]);
+        }
+        assert_eq!(history.len(), 3);
+
+        let mut records = history.iter();
+        for i in 2..5u8 {
+            let record = records.next().unwrap();
+            assert_eq!(record.timestamp(), u32::from(i) * 10);
+            assert_eq!(&record.node()[..], &[i; 4]);
+        }
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn dump_state() {
+        let mut history = History::<U2, U4, u8>::new();
+        history.record(0x0102_0304, &[9, 8]);
+
+        let mut dump = [0u8; 16];
+        let mut written = 0;
+        history.dump_state(&mut |bytes| {
+            dump[written..written + bytes.len()].copy_from_slice(bytes);
+            written += bytes.len();
+        });
+        assert_eq!(
+            &dump[..written],
+            &[2, 0, 1, 0, 1, 0, 4, 3, 2, 1, 2, 0, 9, 8]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "history too large")]
+    fn dump_state_too_large() {
+        let history = History::<U1, U65536, u8>::new();
+        history.dump_state(&mut |_| {});
+    }
+}
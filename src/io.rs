@@ -0,0 +1,72 @@
+use crate::{DMANodeMut, Node};
+use core::mem::MaybeUninit;
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+/// Does a single read from `reader` into the free space of `node` and commits what was read,
+/// returns how many bytes were read. The reader is not called if the node is already full.
+pub fn fill_from_reader<R>(
+    node: &mut impl DMANodeMut<u8>,
+    reader: &mut R,
+) -> Result<usize, R::Error>
+where
+    R: embedded_io::Read,
+{
+    let len = node.len();
+    if len == node.max_len() {
+        return Ok(0);
+    }
+
+    match reader.read(&mut node.write()[len..]) {
+        Ok(count) => {
+            node.commit(len + count);
+            Ok(count)
+        }
+        Err(e) => {
+            node.commit(len);
+            Err(e)
+        }
+    }
+}
+
+impl<N> Node<N, u8>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    /// Does a single read from `reader` into the free space of the node, see the free function
+    /// `fill_from_reader`.
+    #[inline]
+    pub fn fill_from_reader<R>(&mut self, reader: &mut R) -> Result<usize, R::Error>
+    where
+        R: embedded_io::Read,
+    {
+        fill_from_reader(self, reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{typenum::consts::*, DMANodeMut, Node};
+
+    #[test]
+    fn fill_from_reader() {
+        let mut reader = &[1u8, 2, 3, 4, 5, 6][..];
+        let mut node = Node::<U4, u8>::new();
+        node.write_slice(&[0]);
+
+        assert_eq!(node.fill_from_reader(&mut reader), Ok(3));
+        assert_eq!(&node[..], &[0, 1, 2, 3]);
+        assert_eq!(node.fill_from_reader(&mut reader), Ok(0));
+        assert_eq!(reader, &[4, 5, 6]);
+    }
+
+    #[test]
+    fn fill_node_mut() {
+        let mut reader = &[1u8, 2, 3][..];
+        let mut node = Node::<U2, u8>::new();
+        assert_eq!(
+            super::fill_from_reader(&mut node.as_node_mut(), &mut reader),
+            Ok(2)
+        );
+        assert_eq!(&node[..], &[1, 2]);
+    }
+}
@@ -1,23 +1,175 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use as_slice::{AsMutSlice, AsSlice};
 use core::{
     default::Default,
     fmt,
-    mem::MaybeUninit,
+    mem::{self, ManuallyDrop, MaybeUninit},
     ops::{Deref, DerefMut, Drop},
     ptr, slice,
 };
 use generic_array::{typenum::marker_traits::Unsigned, ArrayLength, GenericArray};
 
+mod aged;
+mod bits;
+mod channel;
+mod clock;
+mod constraints;
+mod convert;
+mod dedup;
+mod delta;
+#[cfg(feature = "dyn-node")]
+mod dyn_node;
+mod encode;
+mod erased;
+mod format;
+#[cfg(feature = "embedded-hal")]
+mod hal;
+#[cfg(feature = "history")]
+mod history;
+#[cfg(feature = "embedded-io")]
+mod io;
+mod notify;
+mod pack;
+mod persistent;
+pub mod profiles;
+mod promote;
+mod rate;
+mod record;
+mod retransmit;
+#[cfg(feature = "rle")]
+mod rle;
+mod sequence;
+mod stage;
+mod stall;
+// Needs atomic swap, missing on e.g. thumbv6m
+#[cfg(target_has_atomic = "8")]
+mod swap;
+mod window;
+#[cfg(feature = "ws2812")]
+mod ws2812;
+
+pub use aged::Aged;
+pub use bits::{BitOrder, BitReader, BitWriter};
+pub use channel::{demux, ChannelMux, ChannelQuota, QuotaError, QuotaMux};
+pub use clock::Clock;
+pub use constraints::{DmaConstraints, DmaError};
+pub use convert::IntoDmaNode;
+pub use dedup::Dedup;
+pub use delta::{DeltaDecoder, DeltaEncoder};
+#[cfg(feature = "dyn-node")]
+pub use dyn_node::DynNode;
+pub use encode::{encode_bits, SymbolTable, MANCHESTER, NRZ};
+pub use erased::{NodeMut, NodeRef};
+pub use format::{push_fixed_dec, push_i32_dec, push_str, push_u32_dec, push_u32_hex};
+#[cfg(feature = "embedded-hal")]
+pub use hal::Drain;
+#[cfg(feature = "history")]
+pub use history::{History, HistoryRecord};
+#[cfg(feature = "embedded-io")]
+pub use io::fill_from_reader;
+pub use notify::Notify;
+pub use pack::{pack_words, unpack_words, ByteOrder};
+pub use persistent::PersistentNode;
+pub use rate::RateLimiter;
+pub use record::{RecordReader, RecordWriter};
+pub use retransmit::{Pending, RetransmitBuffer, Timeout};
+#[cfg(feature = "rle")]
+pub use rle::{rle_compress, rle_decompress};
+pub use sequence::{Sequence, SequenceTracker, Sequencer, SEQUENCE_LEN};
+pub use stage::{Counted, Pipeline, Stage};
+#[cfg(feature = "rle")]
+pub use stage::{RleCompress, RleDecompress};
+pub use stall::StallDetector;
+#[cfg(target_has_atomic = "8")]
+pub use swap::{Publisher, Reader, Swap};
+pub use window::{SendError, WindowSender};
+#[cfg(feature = "ws2812")]
+pub use ws2812::Ws2812;
+
 pub mod typenum {
     pub use generic_array::typenum::consts;
 }
 
-pub trait DMANode<T>: Deref<Target = [T]> + DerefMut {
-    /// Creates a new node
-    fn new() -> Self;
+/// Traits and common types needed by most drivers, `use dma_node::prelude::*;`.
+pub mod prelude {
+    pub use crate::{
+        ByteNode, Clock, DMANode, DMANodeMut, DMANodeRef, IntoDmaNode, Node, NodeMut, NodeRef,
+        Notify, Stage, WordNode,
+    };
+}
+
+/// Builds an array calling `f` for each index. `GenericArray::generate` isn't used as it builds
+/// the array on top of `mem::uninitialized`, which panics for some element types.
+pub(crate) fn generate<T, N: ArrayLength<T>>(mut f: impl FnMut(usize) -> T) -> GenericArray<T, N> {
+    let mut array = MaybeUninit::<GenericArray<T, N>>::uninit();
+    let first = array.as_mut_ptr() as *mut T;
+    for i in 0..N::USIZE {
+        // Safe as `GenericArray` has the same layout as `[T; N]`
+        unsafe { first.add(i).write(f(i)) };
+    }
+    unsafe { array.assume_init() }
+}
+
+/// Byte pattern written into the unused part of the buffer of new and cleared nodes.
+#[cfg(feature = "poison")]
+pub const POISON: u8 = 0xA5;
+
+/// Read only part of a node, implemented by the nodes and by borrowed views like `NodeRef`.
+pub trait DMANodeRef<T>: Deref<Target = [T]> {
+    /// Reads how many bytes are available.
+    fn len(&self) -> usize;
+
+    /// Checks if the node is empty.
+    fn is_empty(&self) -> bool;
+
+    /// Returns the address of the buffer.
+    fn buffer_address_for_dma(&self) -> usize;
+
+    /// Returns the maximum length of the internal buffer.
+    fn max_len(&self) -> usize;
 
+    /// Returns the number of free elements in the internal buffer
+    #[inline]
+    fn free(&self) -> usize {
+        self.max_len() - self.len()
+    }
+
+    /// Checks if the whole buffer and the current length fulfill the `constraints` of a DMA
+    /// engine, must be used before handing the buffer to the hardware.
+    fn validate_for_dma(&self, constraints: &DmaConstraints) -> Result<(), DmaError> {
+        constraints.validate(
+            self.buffer_address_for_dma(),
+            self.max_len() * mem::size_of::<T>(),
+            self.len(),
+        )
+    }
+
+    /// Copies the contents of the node into `dests` in order, filling each one before moving to
+    /// the next, and returns how many elements were copied.
+    fn copy_to_vectored(&self, dests: &mut [&mut [T]]) -> usize
+    where
+        T: Copy,
+    {
+        let mut src = &self[..];
+        for dest in dests.iter_mut() {
+            if src.is_empty() {
+                break;
+            }
+            let count = dest.len().min(src.len());
+            dest[..count].copy_from_slice(&src[..count]);
+            src = &src[count..];
+        }
+        self.len() - src.len()
+    }
+}
+
+/// Part of a node that doesn't depend on its capacity or how it's created, implemented by the
+/// nodes and by borrowed views like `NodeMut`.
+pub trait DMANodeMut<T>: DMANodeRef<T> + DerefMut {
     /// Gives a `&mut [W]` slice to write into with the maximum size, the `commit` method
     /// must then be used to set the actual number of bytes written.
     ///
@@ -37,12 +189,6 @@ pub trait DMANode<T>: Deref<Target = [T]> + DerefMut {
     /// Clear the node of all data making it empty.
     fn clear(&mut self);
 
-    /// Reads how many bytes are available.
-    fn len(&self) -> usize;
-
-    /// Checks if the node is empty.
-    fn is_empty(&self) -> bool;
-
     /// Sets the length of the internal buffer.
     ///
     /// # Safety
@@ -51,19 +197,57 @@ pub trait DMANode<T>: Deref<Target = [T]> + DerefMut {
     /// initialized.
     unsafe fn set_len(&mut self, len: usize);
 
-    /// Returns the address of the buffer.
-    fn buffer_address_for_dma(&self) -> usize;
+    /// Writes the contents of `srcs` in order into the node, and returns how many elements were
+    /// written. The last source written may be truncated if the node becomes full.
+    fn write_vectored(&mut self, srcs: &[&[T]]) -> usize {
+        let mut count = 0;
+        for src in srcs {
+            let written = self.write_slice(src);
+            count += written;
+            if written < src.len() {
+                break;
+            }
+        }
+        count
+    }
 
-    /// Returns the maximum length of the internal buffer.
-    fn max_len(&self) -> usize;
+    /// Writes the contents of `srcs` in order into the node, nothing is written if there isn't
+    /// enough free space for all of them.
+    fn try_write_vectored(&mut self, srcs: &[&[T]]) -> Result<(), CapacityError> {
+        let total = srcs.iter().map(|src| src.len()).sum::<usize>();
+        if total > self.free() {
+            return Err(CapacityError);
+        }
+        self.write_vectored(srcs);
+        Ok(())
+    }
+}
 
-    /// Returns the number of free elements in the internal buffer
-    #[inline]
-    fn free(&self) -> usize {
-        self.max_len() - self.len()
+pub trait DMANode<T>: DMANodeMut<T> {
+    /// Creates a new node
+    fn new() -> Self;
+}
+
+/// Returned when there isn't enough free space in the node for all the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError;
+
+/// Errors of the encoders and decoders transforming the contents of a node into another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodecError {
+    /// The destination node doesn't have enough free space.
+    Capacity,
+    /// The input is corrupted or truncated.
+    Malformed,
+}
+
+impl From<CapacityError> for CodecError {
+    fn from(_: CapacityError) -> Self {
+        CodecError::Capacity
     }
 }
 
+#[repr(C)]
 pub struct Node<N, W>
 where
     N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
@@ -72,87 +256,80 @@ where
     buf: GenericArray<MaybeUninit<W>, N>,
 }
 
+/// Node of bytes, as used by UARTs and most byte oriented peripherals.
+pub type ByteNode<N> = Node<N, u8>;
+
+/// Node of 32 bit words, as filled by `pack_words`.
+pub type WordNode<N> = Node<N, u32>;
+
 // Heavily inspired by korken89 work
-impl<N, W> DMANode<W> for Node<N, W>
+impl<N, W> DMANodeRef<W> for Node<N, W>
 where
     N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
-    W: Default,
 {
-    fn new() -> Self {
-        Self {
-            len: 0,
-            buf: unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                MaybeUninit::uninit().assume_init()
-            },
-        }
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
     }
 
-    fn write(&mut self) -> &mut [W] {
-        // Initialize memory with a safe value
-        for elem in self.buf.iter_mut().skip(self.len) {
-            unsafe {
-                ptr::write(elem.as_mut_ptr() as *mut W, W::default());
-            }
-        }
-        self.len = N::USIZE; // Set to max so `commit` may shrink it if needed
-
-        unsafe {
-            slice::from_raw_parts_mut(self.buf.as_mut_slice().as_mut_ptr() as *mut _, N::USIZE)
-        }
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    fn commit(&mut self, shrink_to: usize) {
-        // Only shrinking is allowed to remain safe with the `MaybeUninit`
-        if shrink_to < self.len {
-            self.len = shrink_to;
-        }
+    #[inline]
+    fn buffer_address_for_dma(&self) -> usize {
+        self.buf.as_slice().as_ptr() as usize
     }
 
-    fn write_slice(&mut self, buf: &[W]) -> usize {
-        let count = buf.len().min(self.free());
-
-        // Used to write data into the `MaybeUninit`, safe based on the size check above
-        unsafe {
-            ptr::copy_nonoverlapping(
-                buf.as_ptr(),
-                self.buf.as_mut_slice().as_mut_ptr().add(self.len) as *mut W,
-                count,
-            );
-        }
-
-        self.len += count;
-        count
+    #[inline]
+    fn max_len(&self) -> usize {
+        N::USIZE
     }
+}
 
+// The unsafe parts are only in `NodeMut`, which is shared with the other nodes
+impl<N, W> DMANodeMut<W> for Node<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default,
+{
     #[inline]
-    fn clear(&mut self) {
-        self.len = 0;
+    fn write(&mut self) -> &mut [W] {
+        let mut node = self.as_node_mut();
+        node.write();
+        node.into_slice()
     }
 
     #[inline]
-    fn len(&self) -> usize {
-        self.len as usize
+    fn commit(&mut self, shrink_to: usize) {
+        self.as_node_mut().commit(shrink_to)
     }
 
     #[inline]
-    fn is_empty(&self) -> bool {
-        self.len == 0
+    fn write_slice(&mut self, buf: &[W]) -> usize {
+        self.as_node_mut().write_slice(buf)
     }
 
     #[inline]
-    unsafe fn set_len(&mut self, len: usize) {
-        self.len = len;
+    fn clear(&mut self) {
+        self.as_node_mut().clear()
     }
 
     #[inline]
-    fn buffer_address_for_dma(&self) -> usize {
-        self.buf.as_slice().as_ptr() as usize
+    unsafe fn set_len(&mut self, len: usize) {
+        self.as_node_mut().set_len(len)
     }
+}
 
+impl<N, W> DMANode<W> for Node<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default,
+{
     #[inline]
-    fn max_len(&self) -> usize {
-        N::USIZE
+    fn new() -> Self {
+        Node::new()
     }
 }
 
@@ -160,6 +337,33 @@ impl<N, W> Node<N, W>
 where
     N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
 {
+    /// Creates a new node
+    pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut node = Self {
+            len: 0,
+            buf: unsafe {
+                #[allow(clippy::uninit_assumed_init)]
+                MaybeUninit::uninit().assume_init()
+            },
+        };
+        #[cfg(feature = "poison")]
+        node.as_node_mut().poison();
+        node
+    }
+
+    /// Borrows the node as a `NodeRef`, which doesn't carry the capacity in its type.
+    #[inline]
+    pub fn as_node_ref(&self) -> NodeRef<'_, W> {
+        NodeRef::new(&self.buf, self.len)
+    }
+
+    /// Borrows the node as a `NodeMut`, which doesn't carry the capacity in its type.
+    #[inline]
+    pub fn as_node_mut(&mut self) -> NodeMut<'_, W> {
+        NodeMut::new(&mut self.buf, &mut self.len)
+    }
+
     /// Gives the underling buffer to be modified and the already initialized length, the user is
     /// is free to modify it, but must return the correct number of uninitialized elements that
     /// were initialized.
@@ -179,6 +383,26 @@ where
             self.len + count
         }
     }
+
+    /// Decomposes the node into its raw buffer and the number of initialized elements, the
+    /// elements in `..len` are now owned by the caller and won't be dropped by the node.
+    pub fn take_buffer(self) -> (GenericArray<MaybeUninit<W>, N>, usize) {
+        let this = ManuallyDrop::new(self);
+        // Safe as `this` is never used or dropped after the buffer is moved out
+        let buf = unsafe { ptr::read(&this.buf) };
+        (buf, this.len)
+    }
+
+    /// Reconstructs a node from a raw buffer and the number of initialized elements, usually
+    /// obtained from `take_buffer`.
+    ///
+    /// # Safety
+    ///
+    /// `len` must not be greater than `N` and all elements in `..len` must have been initialized,
+    /// otherwise there will be a risk of accessing uninitialized data, which is undefined behavior.
+    pub unsafe fn from_parts(buf: GenericArray<MaybeUninit<W>, N>, len: usize) -> Self {
+        Self { len, buf }
+    }
 }
 
 impl<N, W> Deref for Node<N, W>
@@ -189,9 +413,7 @@ where
 
     fn deref(&self) -> &Self::Target {
         // Safe as it uses the internal length of valid data
-        unsafe {
-            slice::from_raw_parts(self.buf.as_slice().as_ptr() as *const _, self.len as usize)
-        }
+        unsafe { slice::from_raw_parts(self.buf.as_slice().as_ptr() as *const _, self.len) }
     }
 }
 
@@ -201,12 +423,16 @@ where
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         // Safe as it uses the internal length of valid data
-        unsafe {
-            slice::from_raw_parts_mut(
-                self.buf.as_mut_slice().as_ptr() as *mut _,
-                self.len as usize,
-            )
-        }
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_slice().as_ptr() as *mut _, self.len) }
+    }
+}
+
+impl<N, W> Default for Node<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+{
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -223,19 +449,34 @@ where
     }
 }
 
+impl<N> Node<N, u8>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    /// Appends `s` to the node, nothing is written if there isn't enough free space for all of it.
+    #[inline]
+    pub fn push_str(&mut self, s: &str) -> Result<(), CapacityError> {
+        push_str(self, s)
+    }
+}
+
+impl<'a, N> Extend<&'a str> for Node<N, u8>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    /// Appends the strings until the node is full, the last one may be truncated, always on a
+    /// `char` boundary so the node stays valid UTF-8.
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        format::extend_str(self, iter)
+    }
+}
+
 impl<N> fmt::Write for Node<N, u8>
 where
     N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
 {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        let free = self.free();
-
-        if s.len() > free {
-            Err(fmt::Error)
-        } else {
-            self.write_slice(s.as_bytes());
-            Ok(())
-        }
+        push_str(self, s).map_err(|_| fmt::Error)
     }
 }
 
@@ -273,7 +514,7 @@ where
 mod tests {
 
     use crate::typenum::consts::*;
-    use crate::{DMANode, Node};
+    use crate::{CapacityError, DMANode, DMANodeMut, DMANodeRef, DmaConstraints, DmaError, Node};
     use core::{fmt::Write, ptr};
 
     const DATA: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
@@ -288,6 +529,16 @@ mod tests {
         assert_eq!(node.max_len(), 8);
     }
 
+    #[test]
+    fn generic_new() {
+        fn filled<T: DMANode<u8>>() -> T {
+            let mut node = T::new();
+            node.write_slice(DATA);
+            node
+        }
+        assert_eq!(&filled::<Node<U8, u8>>()[..], DATA);
+    }
+
     #[test]
     fn write_commit() {
         let mut node = Node::<U9, u8>::new();
@@ -322,4 +573,150 @@ mod tests {
         assert_eq!(&node[..], [text, text2].concat().as_bytes());
         assert!(write!(node, "{}", text).is_err());
     }
+
+    #[test]
+    fn push_str_extend() {
+        let mut node = Node::<U8, u8>::new();
+        node.push_str("abc").unwrap();
+        assert_eq!(node.push_str("defghi"), Err(CapacityError));
+        assert_eq!(&node[..], b"abc");
+
+        node.extend(["de", "fghi", "j"].iter().copied());
+        assert_eq!(&node[..], b"abcdefgh");
+
+        let mut node = Node::<U3, u8>::new();
+        node.extend(["o", "lá", "!"].iter().copied());
+        assert_eq!(core::str::from_utf8(&node), Ok("ol"));
+    }
+
+    #[test]
+    fn take_buffer_from_parts() {
+        let mut node = Node::<U8, u8>::new();
+        node.write_slice(&DATA[..5]);
+        let (buf, len) = node.take_buffer();
+        assert_eq!(len, 5);
+        let mut node = unsafe { Node::<U8, u8>::from_parts(buf, len) };
+        assert_eq!(&node[..], &DATA[..5]);
+        node.write_slice(&DATA[5..]);
+        assert_eq!(&node[..], DATA);
+    }
+
+    #[test]
+    fn copy_to_vectored() {
+        let mut node = Node::<U8, u8>::new();
+        node.write_slice(&DATA[..7]);
+
+        let (mut header, mut payload, mut crc) = ([0; 2], [0; 4], [0; 2]);
+        let copied = node.copy_to_vectored(&mut [&mut header, &mut [], &mut payload, &mut crc]);
+        assert_eq!(copied, 7);
+        assert_eq!(header, [1, 2]);
+        assert_eq!(payload, [3, 4, 5, 6]);
+        assert_eq!(crc, [7, 0]);
+
+        let mut small = [0; 3];
+        assert_eq!(node.copy_to_vectored(&mut [&mut small]), 3);
+    }
+
+    #[test]
+    fn write_vectored() {
+        let mut node = Node::<U8, u8>::new();
+        let srcs: &[&[u8]] = &[&DATA[..2], &[], &DATA[2..6]];
+        node.try_write_vectored(srcs).unwrap();
+        assert_eq!(&node[..], &DATA[..6]);
+
+        assert_eq!(node.try_write_vectored(srcs), Err(CapacityError));
+        assert_eq!(node.len(), 6);
+        assert_eq!(node.write_vectored(srcs), 2);
+        assert_eq!(&node[..], &[1, 2, 3, 4, 5, 6, 1, 2]);
+    }
+
+    #[test]
+    fn validate_for_dma() {
+        let mut node = Node::<U8, u32>::new();
+        node.write_slice(&[1, 2, 3]);
+        let start = node.buffer_address_for_dma();
+        let mut constraints = DmaConstraints {
+            alignment: 4,
+            address_range: start..=start + 8 * 4 - 1,
+            max_count: 3,
+        };
+        assert_eq!(node.validate_for_dma(&constraints), Ok(()));
+
+        node.write_slice(&[4]);
+        assert_eq!(node.validate_for_dma(&constraints), Err(DmaError::TooLong));
+
+        constraints.max_count = 8;
+        constraints.address_range = start..=start + 8 * 4 - 2;
+        assert_eq!(
+            node.validate_for_dma(&constraints),
+            Err(DmaError::OutOfRange)
+        );
+
+        constraints.address_range = 0..=usize::MAX;
+        constraints.alignment = 4096;
+        if start % 4096 != 0 {
+            assert_eq!(
+                node.validate_for_dma(&constraints),
+                Err(DmaError::Misaligned)
+            );
+        }
+    }
+
+    #[cfg(feature = "poison")]
+    #[test]
+    fn poison() {
+        let mut node = Node::<U8, u8>::new();
+        node.write_slice(DATA);
+        node.clear();
+        unsafe {
+            node.write_with(|buf, _len| {
+                for elem in buf.iter() {
+                    assert_eq!(elem.as_ptr().read(), crate::POISON);
+                }
+                0
+            });
+        }
+    }
+}
+
+/// Names every feature gated item, so building the tests with any combination of features checks
+/// that each feature still provides its API on its own.
+#[cfg(test)]
+mod features {
+    #[allow(unused_imports)]
+    use crate::typenum::consts::*;
+
+    #[test]
+    fn features() {
+        #[cfg(feature = "poison")]
+        let _ = crate::POISON;
+        #[cfg(feature = "history")]
+        let _: Option<crate::History<U2, U4, u8>> = None;
+        #[cfg(feature = "embedded-hal")]
+        let _ = crate::Drain::new();
+        #[cfg(feature = "embedded-io")]
+        let _ = crate::ByteNode::<U4>::fill_from_reader::<&[u8]>;
+        #[cfg(feature = "rle")]
+        let _ = (crate::RleCompress, crate::RleDecompress);
+        #[cfg(feature = "alloc")]
+        let _ = crate::ByteNode::<U4>::leak;
+        #[cfg(feature = "dyn-node")]
+        let _: Option<crate::DynNode<'_, u8>> = None;
+        #[cfg(feature = "ws2812")]
+        let _: Option<crate::Ws2812> = None;
+        #[cfg(feature = "stm32f4")]
+        let _ = (
+            crate::profiles::stm32f4::DMA,
+            crate::profiles::stm32f4::DMA_FMC,
+        );
+        #[cfg(feature = "stm32h7")]
+        let _ = crate::profiles::stm32h7::MDMA;
+        #[cfg(feature = "nrf52")]
+        let _ = (
+            crate::profiles::nrf52::NRF52832_EASYDMA,
+            crate::profiles::nrf52::NRF52840_EASYDMA,
+        );
+        #[cfg(feature = "rp2040")]
+        let _ = crate::profiles::rp2040::DMA;
+    }
 }
@@ -0,0 +1,26 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Signal raised when data becomes available or a node is freed, decoupling the interrupt
+/// handlers from whatever wakes the consumer.
+pub trait Notify {
+    /// Signals the waiting side.
+    fn notify(&self);
+}
+
+impl<F> Notify for F
+where
+    F: Fn(),
+{
+    #[inline]
+    fn notify(&self) {
+        self()
+    }
+}
+
+/// Sets the flag, for consumers polling it from their main loop.
+impl Notify for AtomicBool {
+    #[inline]
+    fn notify(&self) {
+        self.store(true, Ordering::Release);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{CapacityError, DMANodeMut};
+
+/// Order of the bytes inside a packed word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// The first byte goes into the least significant byte of the word.
+    LittleEndian,
+    /// The first byte goes into the most significant byte of the word.
+    BigEndian,
+}
+
+/// Packs the bytes of `src` into 32 bit words appended to `dest`, the last word is completed with
+/// `padding` bytes if needed. Nothing is written if all the words don't fit.
+pub fn pack_words(
+    src: &[u8],
+    dest: &mut impl DMANodeMut<u32>,
+    order: ByteOrder,
+    padding: u8,
+) -> Result<(), CapacityError> {
+    if (src.len() + 3) / 4 > dest.free() {
+        return Err(CapacityError);
+    }
+
+    for chunk in src.chunks(4) {
+        let mut bytes = [padding; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let word = match order {
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+        };
+        dest.write_slice(&[word]);
+    }
+    Ok(())
+}
+
+/// Unpacks the first `len` bytes out of the words in `src` and appends them to `dest`, the rest
+/// of the last word is considered padding. Nothing is written if all the bytes don't fit.
+///
+/// # Panics
+///
+/// If `src` holds less than `len` bytes.
+pub fn unpack_words(
+    src: &[u32],
+    len: usize,
+    dest: &mut impl DMANodeMut<u8>,
+    order: ByteOrder,
+) -> Result<(), CapacityError> {
+    assert!(len <= src.len() * 4);
+    if len > dest.free() {
+        return Err(CapacityError);
+    }
+
+    let mut left = len;
+    for word in src {
+        let bytes = match order {
+            ByteOrder::LittleEndian => word.to_le_bytes(),
+            ByteOrder::BigEndian => word.to_be_bytes(),
+        };
+        let count = left.min(4);
+        dest.write_slice(&bytes[..count]);
+        left -= count;
+        if left == 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_words, unpack_words, ByteOrder};
+    use crate::{typenum::consts::*, CapacityError, DMANodeMut, DMANodeRef, Node};
+
+    const BYTES: &[u8] = &[1, 2, 3, 4, 5, 6];
+
+    #[test]
+    fn pack_unpack() {
+        let mut words = Node::<U2, u32>::new();
+        pack_words(BYTES, &mut words, ByteOrder::BigEndian, 0xFF).unwrap();
+        assert_eq!(&words[..], &[0x0102_0304, 0x0506_FFFF]);
+
+        let mut bytes = Node::<U8, u8>::new();
+        unpack_words(&words, BYTES.len(), &mut bytes, ByteOrder::BigEndian).unwrap();
+        assert_eq!(&bytes[..], BYTES);
+
+        words.clear();
+        pack_words(BYTES, &mut words, ByteOrder::LittleEndian, 0).unwrap();
+        assert_eq!(&words[..], &[0x0403_0201, 0x0000_0605]);
+        bytes.clear();
+        unpack_words(&words, 5, &mut bytes, ByteOrder::LittleEndian).unwrap();
+        assert_eq!(&bytes[..], &BYTES[..5]);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut words = Node::<U1, u32>::new();
+        assert_eq!(
+            pack_words(BYTES, &mut words, ByteOrder::LittleEndian, 0),
+            Err(CapacityError)
+        );
+        assert!(words.is_empty());
+
+        let mut bytes = Node::<U4, u8>::new();
+        assert_eq!(
+            unpack_words(&[0, 0], 5, &mut bytes, ByteOrder::LittleEndian),
+            Err(CapacityError)
+        );
+        assert!(bytes.is_empty());
+    }
+}
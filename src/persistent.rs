@@ -0,0 +1,130 @@
+use crate::{DMANodeRef, Node};
+use core::{
+    mem::{self, MaybeUninit},
+    ptr,
+};
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+const MAGIC: u32 = 0xD3A0_0DE5;
+
+/// A node with a stable layout and an integrity header, meant to be placed in memory that
+/// survives a soft reset (e.g. backup SRAM), so it can be re-adopted with `recover` after reboot.
+///
+/// The header is a magic number and a CRC-32 of the node's metadata, see `recover` for the
+/// requirements on `W`.
+#[repr(C)]
+pub struct PersistentNode<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+{
+    magic: u32,
+    crc: u32,
+    node: Node<N, W>,
+}
+
+impl<N, W> PersistentNode<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default + Copy,
+{
+    /// Creates a new empty node with a valid header.
+    pub fn new() -> Self {
+        let mut this = Self {
+            magic: MAGIC,
+            crc: 0,
+            node: Node::new(),
+        };
+        this.seal();
+        this
+    }
+
+    /// Initializes an empty node in place, overwriting whatever was there.
+    pub fn init(this: &mut MaybeUninit<Self>) -> &mut Self {
+        this.write(Self::new())
+    }
+
+    /// Validates the header of a node left in memory before a reset and re-adopts it, `None` is
+    /// returned if the header is not valid. `init` should then be used to start over.
+    ///
+    /// # Safety
+    ///
+    /// The memory must still be physically holding data, e.g. a RAM section that isn't cleared
+    /// on reset, whose contents are either garbage or a node written by this same firmware.
+    ///
+    /// The header only covers the metadata, so the contents may be garbage even if the node is
+    /// recovered. `W` must be valid for any bit pattern, e.g. an integer type, reading garbage as
+    /// types like `bool` or `char` is undefined behavior.
+    pub unsafe fn recover(this: &mut MaybeUninit<Self>) -> Option<&mut Self> {
+        let p = this.as_mut_ptr();
+        let magic = ptr::read_volatile(ptr::addr_of!((*p).magic));
+        let crc = ptr::read_volatile(ptr::addr_of!((*p).crc));
+        let len = ptr::read_volatile(ptr::addr_of!((*p).node.len));
+
+        if magic == MAGIC && len <= N::USIZE && crc == metadata_crc::<N, W>(len) {
+            Some(&mut *p)
+        } else {
+            None
+        }
+    }
+
+    /// Gives the inner node to be modified, the header is updated afterwards.
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut Node<N, W>) -> R) -> R {
+        let ret = f(&mut self.node);
+        self.seal();
+        ret
+    }
+
+    /// Returns the inner node.
+    #[inline]
+    pub fn node(&self) -> &Node<N, W> {
+        &self.node
+    }
+
+    fn seal(&mut self) {
+        self.crc = metadata_crc::<N, W>(self.node.len());
+    }
+}
+
+impl<N, W> Default for PersistentNode<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+    W: Default + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CRC-32 (IEEE) of the length, capacity and element size, so a node recovered by a firmware with
+/// a different layout is also rejected.
+fn metadata_crc<N: Unsigned, W>(len: usize) -> u32 {
+    let mut crc = !0u32;
+    let fields = [len as u32, N::U32, mem::size_of::<W>() as u32];
+    for byte in fields.iter().flat_map(|field| field.to_le_bytes()) {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PersistentNode;
+    use crate::{typenum::consts::*, DMANodeMut};
+    use core::mem::MaybeUninit;
+
+    #[test]
+    fn recover() {
+        let mut memory = MaybeUninit::<PersistentNode<U8, u8>>::uninit();
+        PersistentNode::init(&mut memory).update(|node| node.write_slice(&[1, 2, 3]));
+
+        let node = unsafe { PersistentNode::recover(&mut memory) }.unwrap();
+        assert_eq!(&node.node()[..], &[1, 2, 3]);
+
+        node.crc ^= 1;
+        assert!(unsafe { PersistentNode::recover(&mut memory) }.is_none());
+    }
+}
@@ -0,0 +1,70 @@
+//! `DmaConstraints` for the DMA engines of common MCU families, each family is enabled by the
+//! feature with the same name.
+//!
+//! The alignment of the elements is already guaranteed by the node type, the profiles only
+//! require extra alignment when the engine does.
+
+#[cfg(feature = "stm32f4")]
+pub mod stm32f4 {
+    use crate::DmaConstraints;
+
+    /// DMA1 and DMA2 memory side for buffers in the internal SRAM, the CCM RAM at `0x1000_0000`
+    /// is not reachable by them. See `DMA_FMC` for external memory.
+    pub const DMA: DmaConstraints = DmaConstraints {
+        alignment: 1,
+        address_range: 0x2000_0000..=0x3FFF_FFFF,
+        max_count: 0xFFFF,
+    };
+
+    /// DMA1 and DMA2 memory side for buffers in external memory behind the FSMC/FMC, e.g. SDRAM
+    /// on the F42x/F46x parts.
+    pub const DMA_FMC: DmaConstraints = DmaConstraints {
+        alignment: 1,
+        address_range: 0x6000_0000..=0xDFFF_FFFF,
+        max_count: 0xFFFF,
+    };
+}
+
+#[cfg(feature = "stm32h7")]
+pub mod stm32h7 {
+    use crate::DmaConstraints;
+
+    /// MDMA, it reaches all the memories including the TCMs, but the block size is given in
+    /// bytes, so the count only matches for byte nodes.
+    pub const MDMA: DmaConstraints = DmaConstraints {
+        alignment: 1,
+        address_range: 0..=usize::MAX,
+        max_count: 0x1_0000,
+    };
+}
+
+#[cfg(feature = "nrf52")]
+pub mod nrf52 {
+    use crate::DmaConstraints;
+
+    /// EasyDMA on the nRF52832, it can only access the data RAM and `MAXCNT` is 8 bits wide.
+    pub const NRF52832_EASYDMA: DmaConstraints = DmaConstraints {
+        alignment: 1,
+        address_range: 0x2000_0000..=0x2000_FFFF,
+        max_count: 0xFF,
+    };
+
+    /// EasyDMA on the nRF52840, it can only access the data RAM and `MAXCNT` is 16 bits wide.
+    pub const NRF52840_EASYDMA: DmaConstraints = DmaConstraints {
+        alignment: 1,
+        address_range: 0x2000_0000..=0x2003_FFFF,
+        max_count: 0xFFFF,
+    };
+}
+
+#[cfg(feature = "rp2040")]
+pub mod rp2040 {
+    use crate::DmaConstraints;
+
+    /// The DMA is a bus master that reaches the whole address space, `TRANS_COUNT` is 32 bits wide.
+    pub const DMA: DmaConstraints = DmaConstraints {
+        alignment: 1,
+        address_range: 0..=usize::MAX,
+        max_count: u32::MAX as usize,
+    };
+}
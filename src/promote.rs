@@ -0,0 +1,56 @@
+use crate::Node;
+use core::mem::MaybeUninit;
+use generic_array::{typenum::Unsigned, ArrayLength};
+
+impl<N, W> Node<N, W>
+where
+    N: ArrayLength<MaybeUninit<W>> + Unsigned + 'static,
+{
+    /// Moves the node to the heap and leaks it, giving a `'static` handle that can be shared with
+    /// interrupt handlers. The node is never dropped.
+    #[cfg(feature = "alloc")]
+    pub fn leak(self) -> &'static mut Self {
+        alloc::boxed::Box::leak(alloc::boxed::Box::new(self))
+    }
+
+    /// Promotes a borrow of the node to `'static`, for nodes placed in buffers that are
+    /// initialized once and live until reset.
+    ///
+    /// # Safety
+    ///
+    /// The node must never be moved, dropped or accessed through any other reference while the
+    /// returned handle, or anything derived from it, is alive. In practice the node should be in a
+    /// `static` that is only handed out once.
+    #[inline]
+    pub unsafe fn assume_static(&mut self) -> &'static mut Self {
+        &mut *(self as *mut Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{typenum::consts::*, DMANodeMut, Node};
+    use core::{mem::MaybeUninit, ptr};
+
+    // Only handed out once, by the `assume_static` test
+    static mut NODE: MaybeUninit<Node<U8, u8>> = MaybeUninit::uninit();
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn leak() {
+        let mut node = Node::<U8, u8>::new();
+        node.write_slice(b"leak");
+        let node: &'static mut Node<U8, u8> = node.leak();
+        node.write_slice(b"ed");
+        assert_eq!(&node[..], b"leaked");
+    }
+
+    #[test]
+    fn assume_static() {
+        let node = unsafe { &mut *ptr::addr_of_mut!(NODE) }.write(Node::new());
+        // `node` isn't used anymore, the node is only accessed through the handle from now on
+        let handle: &'static mut Node<U8, u8> = unsafe { node.assume_static() };
+        handle.write_slice(b"static");
+        assert_eq!(&handle[..], b"static");
+    }
+}
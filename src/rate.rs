@@ -0,0 +1,146 @@
+use crate::{Clock, DMANodeRef};
+
+/// Token bucket rate limiter, to keep bursty producers from saturating a shared link.
+///
+/// It allows `rate` elements every `period` ticks of the clock, with bursts of up to `burst`
+/// elements, the consumer should check `admit` before starting the transfer of each node.
+pub struct RateLimiter<C> {
+    clock: C,
+    rate: u32,
+    period: u32,
+    burst: u32,
+    // Tokens scaled by `period`, so partial tokens aren't lost between refills
+    tokens: u64,
+    last: u32,
+    admitted: u32,
+    deferred: u32,
+}
+
+impl<C: Clock> RateLimiter<C> {
+    /// Creates a new rate limiter with a full bucket.
+    ///
+    /// # Panics
+    ///
+    /// If `period` or `burst` is zero.
+    pub fn new(clock: C, rate: u32, period: u32, burst: u32) -> Self {
+        assert!(period != 0 && burst != 0);
+        let last = clock.now();
+        Self {
+            clock,
+            rate,
+            period,
+            burst,
+            tokens: u64::from(burst) * u64::from(period),
+            last,
+            admitted: 0,
+            deferred: 0,
+        }
+    }
+
+    /// Checks if `count` elements can be sent now and takes them from the bucket if so,
+    /// otherwise the frame is counted as deferred.
+    ///
+    /// A frame larger than the burst size could never fit in the bucket, so it is admitted once
+    /// the bucket is full instead, taking all of its tokens.
+    pub fn try_acquire(&mut self, count: usize) -> bool {
+        let now = self.clock.now();
+        let elapsed = now.wrapping_sub(self.last);
+        self.last = now;
+
+        let max = u64::from(self.burst) * u64::from(self.period);
+        self.tokens = self
+            .tokens
+            .saturating_add(u64::from(elapsed) * u64::from(self.rate))
+            .min(max);
+
+        let needed = (count as u64)
+            .saturating_mul(u64::from(self.period))
+            .min(max);
+        if needed <= self.tokens {
+            self.tokens -= needed;
+            self.admitted = self.admitted.wrapping_add(1);
+            true
+        } else {
+            self.deferred = self.deferred.wrapping_add(1);
+            false
+        }
+    }
+
+    /// Checks if the whole `node` can be sent now, see `try_acquire`.
+    #[inline]
+    pub fn admit<W>(&mut self, node: &impl DMANodeRef<W>) -> bool {
+        self.try_acquire(node.len())
+    }
+
+    /// Changes the rate and burst size, the tokens already in the bucket are kept up to the new
+    /// burst size.
+    ///
+    /// # Panics
+    ///
+    /// If `period` or `burst` is zero.
+    pub fn set_rate(&mut self, rate: u32, period: u32, burst: u32) {
+        assert!(period != 0 && burst != 0);
+        let tokens = self.tokens / u64::from(self.period);
+        self.rate = rate;
+        self.period = period;
+        self.burst = burst;
+        self.tokens = tokens.min(u64::from(burst)) * u64::from(period);
+    }
+
+    /// Returns how many frames were admitted.
+    #[inline]
+    pub fn admitted(&self) -> u32 {
+        self.admitted
+    }
+
+    /// Returns how many frames were deferred for exceeding the rate.
+    #[inline]
+    pub fn deferred(&self) -> u32 {
+        self.deferred
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use crate::{typenum::consts::*, DMANodeMut, Node};
+    use core::cell::Cell;
+
+    #[test]
+    fn token_bucket() {
+        let time = Cell::new(0u32);
+        // 3 elements every 4 ticks
+        let mut limiter = RateLimiter::new(|| time.get(), 3, 4, 8);
+        let mut node = Node::<U8, u8>::new();
+        node.write_slice(&[0; 6]);
+
+        assert!(limiter.admit(&node));
+        assert!(!limiter.admit(&node));
+
+        time.set(4);
+        assert!(!limiter.admit(&node));
+        time.set(6);
+        assert!(limiter.admit(&node));
+
+        // The bucket never holds more than the burst size
+        time.set(1000);
+        assert!(limiter.try_acquire(8));
+        assert!(!limiter.try_acquire(1));
+
+        assert_eq!(limiter.admitted(), 3);
+        assert_eq!(limiter.deferred(), 3);
+    }
+
+    #[test]
+    fn larger_than_burst() {
+        let time = Cell::new(0u32);
+        let mut limiter = RateLimiter::new(|| time.get(), 1, 1, 4);
+
+        // Waits for a full bucket and then takes all of it
+        assert!(limiter.try_acquire(1));
+        assert!(!limiter.try_acquire(10));
+        time.set(1);
+        assert!(limiter.try_acquire(10));
+        assert!(!limiter.try_acquire(1));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{CapacityError, Clock, DMANodeMut};
+
+/// Largest LEB128 encoding of a `u32`.
+const MAX_VARINT: usize = 5;
+
+/// Appends timestamped records to byte nodes, each one prefixed by its timestamp and length.
+///
+/// Both are LEB128 encoded, the timestamp of the first record in a node is absolute and the next
+/// ones are deltas from the previous record, so each node can be decoded by itself with a
+/// `RecordReader`.
+pub struct RecordWriter<C> {
+    clock: C,
+    last: u32,
+}
+
+/// Iterates over the `(timestamp, data)` records written by a `RecordWriter` into a node.
+pub struct RecordReader<'a> {
+    data: &'a [u8],
+    timestamp: Option<u32>,
+}
+
+impl<C: Clock> RecordWriter<C> {
+    /// Creates a new writer taking timestamps from `clock`.
+    pub fn new(clock: C) -> Self {
+        Self { clock, last: 0 }
+    }
+
+    /// Appends `data` as a new record, nothing is written if the whole record doesn't fit.
+    pub fn append(
+        &mut self,
+        node: &mut impl DMANodeMut<u8>,
+        data: &[u8],
+    ) -> Result<(), CapacityError> {
+        let now = self.clock.now();
+        let stamp = if node.is_empty() {
+            now
+        } else {
+            now.wrapping_sub(self.last)
+        };
+
+        let mut header = [0; 2 * MAX_VARINT];
+        let mut len = encode_varint(stamp, &mut header);
+        len += encode_varint(data.len() as u32, &mut header[len..]);
+        if len + data.len() > node.free() {
+            return Err(CapacityError);
+        }
+
+        node.write_slice(&header[..len]);
+        node.write_slice(data);
+        self.last = now;
+        Ok(())
+    }
+}
+
+impl<'a> RecordReader<'a> {
+    /// Creates a reader over the contents of a node.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            timestamp: None,
+        }
+    }
+}
+
+impl<'a> Iterator for RecordReader<'a> {
+    type Item = (u32, &'a [u8]);
+
+    /// Returns the next record, the iteration ends early if the data is malformed.
+    fn next(&mut self) -> Option<Self::Item> {
+        let (stamp, rest) = decode_varint(self.data)?;
+        let (len, rest) = decode_varint(rest)?;
+        let len = len as usize;
+        if len > rest.len() {
+            self.data = &[];
+            return None;
+        }
+
+        let timestamp = match self.timestamp {
+            Some(last) => last.wrapping_add(stamp),
+            None => stamp,
+        };
+        self.timestamp = Some(timestamp);
+        self.data = &rest[len..];
+        Some((timestamp, &rest[..len]))
+    }
+}
+
+fn encode_varint(mut value: u32, out: &mut [u8]) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[len] = byte;
+            return len + 1;
+        }
+        out[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+fn decode_varint(data: &[u8]) -> Option<(u32, &[u8])> {
+    let mut value = 0u32;
+    for (i, byte) in data.iter().take(MAX_VARINT).enumerate() {
+        value |= u32::from(byte & 0x7F) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, &data[i + 1..]));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordReader, RecordWriter};
+    use crate::{typenum::consts::*, CapacityError, Node};
+    use core::cell::Cell;
+
+    #[test]
+    fn write_read() {
+        let time = Cell::new(0xFFFF_FFF0u32);
+        let mut writer = RecordWriter::new(|| time.get());
+        let mut node = Node::<U32, u8>::new();
+
+        writer.append(&mut node, b"boot").unwrap();
+        time.set(time.get().wrapping_add(300));
+        writer.append(&mut node, b"").unwrap();
+        time.set(time.get().wrapping_add(1));
+        writer.append(&mut node, &[0xAA; 3]).unwrap();
+        assert_eq!(writer.append(&mut node, &[0; 20]), Err(CapacityError));
+
+        let mut reader = RecordReader::new(&node);
+        assert_eq!(reader.next(), Some((0xFFFF_FFF0, &b"boot"[..])));
+        assert_eq!(reader.next(), Some((0x11C, &b""[..])));
+        assert_eq!(reader.next(), Some((0x11D, &[0xAA; 3][..])));
+        assert_eq!(reader.next(), None);
+
+        // A new node starts with an absolute timestamp
+        let mut node = Node::<U8, u8>::new();
+        writer.append(&mut node, b"x").unwrap();
+        assert_eq!(RecordReader::new(&node).next(), Some((0x11D, &b"x"[..])));
+    }
+
+    #[test]
+    fn truncated() {
+        let mut reader = RecordReader::new(&[0x01, 0x05, 0xAA]);
+        assert_eq!(reader.next(), None);
+        let mut reader = RecordReader::new(&[0x80]);
+        assert_eq!(reader.next(), None);
+        assert_eq!(reader.next(), None);
+    }
+}
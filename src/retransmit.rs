@@ -0,0 +1,156 @@
+use crate::Clock;
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength, GenericArray};
+
+/// Sent node waiting for its acknowledgment in a `RetransmitBuffer`.
+pub struct Pending<T> {
+    sequence: u16,
+    node: T,
+    sent_at: u32,
+    retries: u8,
+}
+
+/// What `RetransmitBuffer::poll` found about a node that wasn't acknowledged in time.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Timeout<'a, T> {
+    /// The node must be sent again, it's kept in the buffer.
+    Resend(u16, &'a T),
+    /// The retry limit was reached, the node is given back.
+    GaveUp(u16, T),
+}
+
+/// Keeps up to `D` sent nodes, keyed by their sequence number, until they are acknowledged, so
+/// they can be sent again if the acknowledgment doesn't arrive in time.
+pub struct RetransmitBuffer<D, T, C>
+where
+    D: ArrayLength<Option<Pending<T>>> + Unsigned,
+{
+    entries: GenericArray<Option<Pending<T>>, D>,
+    clock: C,
+    timeout: u32,
+    max_retries: u8,
+    resent: u32,
+    failed: u32,
+}
+
+impl<D, T, C> RetransmitBuffer<D, T, C>
+where
+    D: ArrayLength<Option<Pending<T>>> + Unsigned,
+    C: Clock,
+{
+    /// Creates an empty buffer, nodes are resent if not acknowledged `timeout` ticks after being
+    /// sent, and given up after `max_retries` resends.
+    pub fn new(clock: C, timeout: u32, max_retries: u8) -> Self {
+        Self {
+            entries: crate::generate(|_| None),
+            clock,
+            timeout,
+            max_retries,
+            resent: 0,
+            failed: 0,
+        }
+    }
+
+    /// Stores a node that was just sent and returns it, so its transfer can be started. The node is
+    /// given back if the buffer is full.
+    pub fn insert(&mut self, sequence: u16, node: T) -> Result<&T, T> {
+        match self.entries.iter_mut().find(|entry| entry.is_none()) {
+            Some(entry) => {
+                let pending = entry.insert(Pending {
+                    sequence,
+                    node,
+                    sent_at: self.clock.now(),
+                    retries: 0,
+                });
+                Ok(&pending.node)
+            }
+            None => Err(node),
+        }
+    }
+
+    /// Releases the node with `sequence`, returning it so it can be reused.
+    pub fn ack(&mut self, sequence: u16) -> Option<T> {
+        self.entries
+            .iter_mut()
+            .find(|entry| matches!(entry, Some(pending) if pending.sequence == sequence))
+            .and_then(|entry| entry.take())
+            .map(|pending| pending.node)
+    }
+
+    /// Looks for a node whose acknowledgment timed out, it should be called periodically until it
+    /// returns `None`. A node to be resent has its timeout restarted.
+    pub fn poll(&mut self) -> Option<Timeout<'_, T>> {
+        let now = self.clock.now();
+        let timeout = self.timeout;
+        let entry = self.entries.iter_mut().find(
+            |entry| matches!(entry, Some(pending) if now.wrapping_sub(pending.sent_at) >= timeout),
+        )?;
+
+        let pending = entry.as_ref()?;
+        if pending.retries >= self.max_retries {
+            self.failed = self.failed.wrapping_add(1);
+            let pending = entry.take()?;
+            return Some(Timeout::GaveUp(pending.sequence, pending.node));
+        }
+
+        self.resent = self.resent.wrapping_add(1);
+        let pending = entry.as_mut()?;
+        pending.retries += 1;
+        pending.sent_at = now;
+        Some(Timeout::Resend(pending.sequence, &pending.node))
+    }
+
+    /// Returns how many nodes are waiting for acknowledgment.
+    pub fn in_flight(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    /// Checks if no nodes are waiting for acknowledgment.
+    pub fn is_idle(&self) -> bool {
+        self.entries.iter().all(|entry| entry.is_none())
+    }
+
+    /// Returns how many times nodes were resent.
+    #[inline]
+    pub fn resent(&self) -> u32 {
+        self.resent
+    }
+
+    /// Returns how many nodes were given up.
+    #[inline]
+    pub fn failed(&self) -> u32 {
+        self.failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RetransmitBuffer, Timeout};
+    use crate::typenum::consts::*;
+    use core::cell::Cell;
+
+    #[test]
+    fn ack_resend_give_up() {
+        let time = Cell::new(0);
+        let mut buffer = RetransmitBuffer::<U2, &str, _>::new(|| time.get(), 10, 1);
+
+        assert_eq!(buffer.insert(1, "a"), Ok(&"a"));
+        time.set(5);
+        buffer.insert(2, "b").unwrap();
+        assert_eq!(buffer.insert(3, "c"), Err("c"));
+        assert_eq!(buffer.in_flight(), 2);
+
+        assert_eq!(buffer.poll(), None);
+        time.set(10);
+        assert_eq!(buffer.poll(), Some(Timeout::Resend(1, &"a")));
+        assert_eq!(buffer.poll(), None);
+
+        assert_eq!(buffer.ack(2), Some("b"));
+        assert_eq!(buffer.ack(2), None);
+
+        time.set(20);
+        assert_eq!(buffer.poll(), Some(Timeout::GaveUp(1, "a")));
+        assert_eq!(buffer.in_flight(), 0);
+        assert!(buffer.is_idle());
+        assert_eq!((buffer.resent(), buffer.failed()), (1, 1));
+    }
+}
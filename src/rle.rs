@@ -0,0 +1,125 @@
+use crate::{CodecError, DMANodeMut};
+
+/// Longest literal or run described by a single header.
+const MAX_RUN: usize = 128;
+
+/// Compresses `src` with PackBits run-length encoding, appending it to `dest`. On error `dest` is
+/// left as it was.
+///
+/// Each header byte `h` is followed by `h + 1` literal bytes if `h < 128`, or by a single byte to
+/// be repeated `257 - h` times if `h > 128`, so incompressible data grows by at most one byte
+/// every 128.
+pub fn rle_compress(src: &[u8], dest: &mut impl DMANodeMut<u8>) -> Result<(), CodecError> {
+    let start = dest.len();
+    let ret = compress(src, dest);
+    if ret.is_err() {
+        dest.commit(start);
+    }
+    ret
+}
+
+/// Decompresses the PackBits encoded `src`, appending it to `dest`. On error `dest` is left as it
+/// was.
+pub fn rle_decompress(src: &[u8], dest: &mut impl DMANodeMut<u8>) -> Result<(), CodecError> {
+    let start = dest.len();
+    let ret = decompress(src, dest);
+    if ret.is_err() {
+        dest.commit(start);
+    }
+    ret
+}
+
+fn compress(src: &[u8], dest: &mut impl DMANodeMut<u8>) -> Result<(), CodecError> {
+    let mut i = 0;
+    while i < src.len() {
+        let run = run_len(&src[i..]);
+        if run >= 3 {
+            put(dest, &[(257 - run) as u8, src[i]])?;
+            i += run;
+            continue;
+        }
+
+        // Literals until the next run worth encoding
+        let literal = i;
+        while i < src.len() && i - literal < MAX_RUN && run_len(&src[i..]) < 3 {
+            i += 1;
+        }
+        put(dest, &[(i - literal - 1) as u8])?;
+        put(dest, &src[literal..i])?;
+    }
+    Ok(())
+}
+
+fn decompress(mut src: &[u8], dest: &mut impl DMANodeMut<u8>) -> Result<(), CodecError> {
+    while let Some((&header, rest)) = src.split_first() {
+        let header = usize::from(header);
+        src = if header < 128 {
+            let literal = rest.get(..=header).ok_or(CodecError::Malformed)?;
+            put(dest, literal)?;
+            &rest[header + 1..]
+        } else if header > 128 {
+            let (&byte, rest) = rest.split_first().ok_or(CodecError::Malformed)?;
+            put(dest, &[byte; MAX_RUN][..257 - header])?;
+            rest
+        } else {
+            rest
+        };
+    }
+    Ok(())
+}
+
+fn run_len(data: &[u8]) -> usize {
+    data.iter()
+        .take(MAX_RUN)
+        .take_while(|&&byte| byte == data[0])
+        .count()
+}
+
+fn put(dest: &mut impl DMANodeMut<u8>, bytes: &[u8]) -> Result<(), CodecError> {
+    if dest.write_slice(bytes) < bytes.len() {
+        Err(CodecError::Capacity)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rle_compress, rle_decompress};
+    use crate::{typenum::consts::*, CodecError, DMANodeMut, Node};
+
+    #[test]
+    fn round_trip() {
+        let mut data = Node::<U256, u8>::new();
+        data.write_slice(&[1, 2, 3, 3]);
+        data.write_slice(&[7; 200]);
+        data.write_slice(&[4, 5]);
+
+        let mut compressed = Node::<U16, u8>::new();
+        rle_compress(&data, &mut compressed).unwrap();
+        assert_eq!(&compressed[..], &[3, 1, 2, 3, 3, 129, 7, 185, 7, 1, 4, 5]);
+
+        let mut decompressed = Node::<U256, u8>::new();
+        rle_decompress(&compressed, &mut decompressed).unwrap();
+        assert_eq!(&decompressed[..], &data[..]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut node = Node::<U4, u8>::new();
+        node.write_slice(&[9]);
+        assert_eq!(
+            rle_compress(&[1, 2, 3, 4], &mut node),
+            Err(CodecError::Capacity)
+        );
+        assert_eq!(
+            rle_decompress(&[2, 1, 2], &mut node),
+            Err(CodecError::Malformed)
+        );
+        assert_eq!(
+            rle_decompress(&[255], &mut node),
+            Err(CodecError::Malformed)
+        );
+        assert_eq!(&node[..], &[9]);
+    }
+}
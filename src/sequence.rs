@@ -0,0 +1,218 @@
+use crate::{CodecError, DMANodeMut, Stage};
+
+/// Size of the sequence number header.
+pub const SEQUENCE_LEN: usize = 2;
+
+/// `Stage` prefixing each frame with a little endian `u16` sequence number, incremented on every
+/// frame and wrapping around.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sequencer {
+    next: u16,
+}
+
+/// Result of `SequenceTracker::track` for a received frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sequence {
+    /// The frame is the one expected.
+    InOrder,
+    /// The given number of frames were lost before this one.
+    Gap(u16),
+    /// The frame was already received, or is older than the last one.
+    Duplicate,
+}
+
+/// Checks the sequence numbers added by a `Sequencer` on received frames to flag gaps and
+/// duplicates.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SequenceTracker {
+    expected: Option<u16>,
+    resync: u16,
+    // Duplicates received in a row, for `resync`
+    consecutive: u16,
+    lost: u32,
+    duplicates: u32,
+}
+
+impl Sequencer {
+    /// Creates a sequencer starting at `first`.
+    pub fn new(first: u16) -> Self {
+        Self { next: first }
+    }
+
+    /// Returns the sequence number of the next frame.
+    #[inline]
+    pub fn next_sequence(&self) -> u16 {
+        self.next
+    }
+}
+
+impl Stage for Sequencer {
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        output.try_write_vectored(&[&self.next.to_le_bytes(), input])?;
+        self.next = self.next.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Reuses the sequence number of the dropped frame, so the receiver doesn't see a gap.
+    #[inline]
+    fn rollback(&mut self) {
+        self.next = self.next.wrapping_sub(1);
+    }
+}
+
+impl SequenceTracker {
+    /// Creates a tracker that synchronizes to the first frame received.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tracker that also resynchronizes after `resync` duplicates in a row, e.g. when
+    /// the sender rebooted and started numbering from the beginning. Zero never resynchronizes.
+    pub fn with_resync(resync: u16) -> Self {
+        Self {
+            resync,
+            ..Self::default()
+        }
+    }
+
+    /// Checks the sequence number of `frame` and returns it along with the payload that follows
+    /// the header, `None` is returned if the frame is too short to have a header.
+    ///
+    /// Numbers up to half the sequence space ahead of the expected one are counted as gaps, the
+    /// ones behind as duplicates, which don't move the expected number. The frame completing
+    /// `resync` duplicates in a row is taken as in order, like the first frame.
+    pub fn track<'a>(&mut self, frame: &'a [u8]) -> Option<(Sequence, &'a [u8])> {
+        if frame.len() < SEQUENCE_LEN {
+            return None;
+        }
+        let (header, payload) = frame.split_at(SEQUENCE_LEN);
+        let number = u16::from_le_bytes([header[0], header[1]]);
+
+        let expected = self.expected.unwrap_or(number);
+        let ahead = number.wrapping_sub(expected);
+        let sequence = if ahead == 0 {
+            Sequence::InOrder
+        } else if ahead < 0x8000 {
+            self.lost = self.lost.wrapping_add(u32::from(ahead));
+            Sequence::Gap(ahead)
+        } else if self.resync != 0 && self.consecutive + 1 >= self.resync {
+            Sequence::InOrder
+        } else {
+            self.consecutive = self.consecutive.saturating_add(1);
+            self.duplicates = self.duplicates.wrapping_add(1);
+            return Some((Sequence::Duplicate, payload));
+        };
+
+        self.consecutive = 0;
+        self.expected = Some(number.wrapping_add(1));
+        Some((sequence, payload))
+    }
+
+    /// Synchronizes again to the next frame received, e.g. after the sender is known to have
+    /// restarted.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.expected = None;
+        self.consecutive = 0;
+    }
+
+    /// Returns how many frames were lost in gaps.
+    #[inline]
+    pub fn lost(&self) -> u32 {
+        self.lost
+    }
+
+    /// Returns how many duplicated frames were received.
+    #[inline]
+    pub fn duplicates(&self) -> u32 {
+        self.duplicates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sequence, SequenceTracker, Sequencer};
+    use crate::{typenum::consts::*, Node, Stage};
+
+    #[test]
+    fn gaps_duplicates() {
+        let mut sequencer = Sequencer::new(0xFFFF);
+        let mut tracker = SequenceTracker::new();
+        let mut frames = [Node::<U4, u8>::new(), Node::new(), Node::new(), Node::new()];
+        for (i, frame) in frames.iter_mut().enumerate() {
+            sequencer.process(&[i as u8], frame).unwrap();
+        }
+        assert_eq!(&frames[0][..], &[0xFF, 0xFF, 0]);
+        assert_eq!(&frames[1][..], &[0, 0, 1]);
+
+        assert_eq!(
+            tracker.track(&frames[0]),
+            Some((Sequence::InOrder, &[0][..]))
+        );
+        assert_eq!(
+            tracker.track(&frames[1]),
+            Some((Sequence::InOrder, &[1][..]))
+        );
+        assert_eq!(
+            tracker.track(&frames[3]),
+            Some((Sequence::Gap(1), &[3][..]))
+        );
+        assert_eq!(
+            tracker.track(&frames[2]),
+            Some((Sequence::Duplicate, &[2][..]))
+        );
+        assert_eq!(
+            tracker.track(&frames[3]),
+            Some((Sequence::Duplicate, &[3][..]))
+        );
+        assert_eq!(tracker.track(&[0]), None);
+        assert_eq!(tracker.lost(), 1);
+        assert_eq!(tracker.duplicates(), 2);
+        assert_eq!(sequencer.next_sequence(), 3);
+        sequencer.rollback();
+        assert_eq!(sequencer.next_sequence(), 2);
+    }
+
+    #[test]
+    fn sender_restart() {
+        let mut tracker = SequenceTracker::new();
+        for number in 100u16..103 {
+            tracker.track(&number.to_le_bytes());
+        }
+        assert_eq!(
+            tracker.track(&0u16.to_le_bytes()),
+            Some((Sequence::Duplicate, &[][..]))
+        );
+        tracker.reset();
+        assert_eq!(
+            tracker.track(&0u16.to_le_bytes()),
+            Some((Sequence::InOrder, &[][..]))
+        );
+        assert_eq!(
+            tracker.track(&1u16.to_le_bytes()),
+            Some((Sequence::InOrder, &[][..]))
+        );
+
+        let mut tracker = SequenceTracker::with_resync(3);
+        tracker.track(&100u16.to_le_bytes());
+        for number in 0u16..2 {
+            assert_eq!(
+                tracker.track(&number.to_le_bytes()),
+                Some((Sequence::Duplicate, &[][..]))
+            );
+        }
+        assert_eq!(
+            tracker.track(&2u16.to_le_bytes()),
+            Some((Sequence::InOrder, &[][..]))
+        );
+        assert_eq!(
+            tracker.track(&3u16.to_le_bytes()),
+            Some((Sequence::InOrder, &[][..]))
+        );
+        assert_eq!(tracker.duplicates(), 2);
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{CodecError, DMANodeMut, DeltaDecoder, DeltaEncoder, Node};
+use core::mem::MaybeUninit;
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+/// A transformation of the contents of a byte node into another, e.g. a compression or framing
+/// step on the TX path.
+pub trait Stage {
+    /// Transforms `input`, appending the result to `output`.
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError>;
+
+    /// Called when a later stage fails after a successful `process`, so the frame is dropped.
+    /// Stages whose state must stay in sync with the receiver use it to undo the last frame.
+    #[inline]
+    fn rollback(&mut self) {}
+
+    /// Chains `next` after this stage, using a node of capacity `N` for the intermediate result.
+    fn then<S, N>(self, next: S) -> Pipeline<Self, S, N>
+    where
+        Self: Sized,
+        S: Stage,
+        N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+    {
+        Pipeline::new(self, next)
+    }
+}
+
+/// Two stages run one after the other, it's also a `Stage`, so longer pipelines are built by
+/// chaining more stages with `then`.
+pub struct Pipeline<A, B, N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    first: A,
+    second: B,
+    middle: Node<N, u8>,
+}
+
+impl<A, B, N> Pipeline<A, B, N>
+where
+    A: Stage,
+    B: Stage,
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    /// Creates a pipeline running `first` and then `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            middle: Node::new(),
+        }
+    }
+
+    /// Returns the stages.
+    pub fn inner_stages(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Consumes the pipeline, returning its stages.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A, B, N> Stage for Pipeline<A, B, N>
+where
+    A: Stage,
+    B: Stage,
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        self.middle.clear();
+        self.first.process(input, &mut self.middle)?;
+        let ret = self.second.process(&self.middle, output);
+        if ret.is_err() {
+            self.first.rollback();
+        }
+        ret
+    }
+
+    fn rollback(&mut self) {
+        self.second.rollback();
+        self.first.rollback();
+    }
+}
+
+impl<N> Stage for DeltaEncoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    #[inline]
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        self.encode(input, output)
+    }
+
+    /// The receiver never got the last frame, which may have been a key frame, so the next one
+    /// must be a key frame too.
+    #[inline]
+    fn rollback(&mut self) {
+        self.reset();
+    }
+}
+
+impl<N> Stage for DeltaDecoder<N>
+where
+    N: ArrayLength<MaybeUninit<u8>> + Unsigned + 'static,
+{
+    #[inline]
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        self.decode(input, output)
+    }
+}
+
+/// Wraps a stage counting its errors, which are still returned so the rest of the pipeline isn't
+/// run. The input that caused each error is also given to a sink, e.g. to forward malformed
+/// frames to an error queue instead of silently dropping them.
+pub struct Counted<S, F = fn(&[u8], CodecError)> {
+    stage: S,
+    sink: F,
+    capacity: u32,
+    malformed: u32,
+}
+
+impl<S: Stage> Counted<S> {
+    /// Wraps `stage`, frames with errors are only counted and dropped.
+    pub fn new(stage: S) -> Self {
+        Self::with_sink(stage, |_, _| {})
+    }
+}
+
+impl<S, F> Counted<S, F>
+where
+    S: Stage,
+    F: FnMut(&[u8], CodecError),
+{
+    /// Wraps `stage`, giving the input and the error to `sink` on every error.
+    pub fn with_sink(stage: S, sink: F) -> Self {
+        Self {
+            stage,
+            sink,
+            capacity: 0,
+            malformed: 0,
+        }
+    }
+
+    /// Returns how many times the output didn't have enough free space.
+    #[inline]
+    pub fn capacity_errors(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Returns how many malformed inputs were seen.
+    #[inline]
+    pub fn malformed_errors(&self) -> u32 {
+        self.malformed
+    }
+
+    /// Returns the wrapped stage.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.stage
+    }
+}
+
+impl<S, F> Stage for Counted<S, F>
+where
+    S: Stage,
+    F: FnMut(&[u8], CodecError),
+{
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        let ret = self.stage.process(input, output);
+        if let Err(e) = ret {
+            match e {
+                CodecError::Capacity => self.capacity = self.capacity.wrapping_add(1),
+                CodecError::Malformed => self.malformed = self.malformed.wrapping_add(1),
+            }
+            (self.sink)(input, e);
+        }
+        ret
+    }
+
+    #[inline]
+    fn rollback(&mut self) {
+        self.stage.rollback();
+    }
+}
+
+/// `Stage` running `rle_compress`.
+#[cfg(feature = "rle")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RleCompress;
+
+#[cfg(feature = "rle")]
+impl Stage for RleCompress {
+    #[inline]
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        crate::rle_compress(input, output)
+    }
+}
+
+/// `Stage` running `rle_decompress`.
+#[cfg(feature = "rle")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RleDecompress;
+
+#[cfg(feature = "rle")]
+impl Stage for RleDecompress {
+    #[inline]
+    fn process<O: DMANodeMut<u8>>(
+        &mut self,
+        input: &[u8],
+        output: &mut O,
+    ) -> Result<(), CodecError> {
+        crate::rle_decompress(input, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counted, Stage};
+    use crate::{
+        typenum::consts::*, CodecError, DMANodeMut, DMANodeRef, DeltaDecoder, DeltaEncoder, Node,
+    };
+    use core::cell::Cell;
+
+    /// Appends the sum of the bytes.
+    struct Checksum;
+
+    impl Stage for Checksum {
+        fn process<O: DMANodeMut<u8>>(
+            &mut self,
+            input: &[u8],
+            output: &mut O,
+        ) -> Result<(), CodecError> {
+            let sum = input.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+            output.try_write_vectored(&[input, &[sum]])?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pipeline() {
+        let mut pipeline = DeltaEncoder::<U4>::new()
+            .then::<_, U8>(Checksum)
+            .then::<_, U8>(Checksum);
+        let mut output = Node::<U8, u8>::new();
+
+        pipeline.process(&[1, 2], &mut output).unwrap();
+        assert_eq!(&output[..], &[0, 1, 2, 3, 6]);
+
+        output.clear();
+        pipeline.process(&[1, 3], &mut output).unwrap();
+        assert_eq!(&output[..], &[1, 0, 1, 2, 4]);
+
+        let mut small = Node::<U4, u8>::new();
+        assert_eq!(
+            pipeline.process(&[1, 3], &mut small),
+            Err(CodecError::Capacity)
+        );
+    }
+
+    #[test]
+    fn rollback() {
+        let mut tx = DeltaEncoder::<U4>::new().then::<_, U8>(Checksum);
+        let mut rx = DeltaDecoder::<U4>::new();
+        let mut encoded = Node::<U8, u8>::new();
+        let mut decoded = Node::<U4, u8>::new();
+
+        tx.process(&[1, 2], &mut encoded).unwrap();
+        rx.decode(&encoded[..encoded.len() - 1], &mut decoded)
+            .unwrap();
+        assert_eq!(&decoded[..], &[1, 2]);
+
+        // The key frame after the reset is dropped by the checksum stage
+        let (mut encoder, checksum) = tx.into_inner();
+        encoder.reset();
+        let mut tx = encoder.then::<_, U8>(checksum);
+        let mut small = Node::<U3, u8>::new();
+        assert_eq!(tx.process(&[7, 8], &mut small), Err(CodecError::Capacity));
+
+        // So the next frame can't be a delta against it
+        encoded.clear();
+        decoded.clear();
+        tx.process(&[7, 8], &mut encoded).unwrap();
+        rx.decode(&encoded[..encoded.len() - 1], &mut decoded)
+            .unwrap();
+        assert_eq!(&decoded[..], &[7, 8]);
+    }
+
+    #[test]
+    fn rx_errors() {
+        let forwarded = Cell::new(0);
+        let mut pipeline = Counted::with_sink(
+            Counted::new(DeltaDecoder::<U4>::new()).then::<_, U8>(Counted::new(Checksum)),
+            |input: &[u8], e| {
+                assert_eq!(e, CodecError::Malformed);
+                forwarded.set(input.len());
+            },
+        );
+        let mut output = Node::<U8, u8>::new();
+
+        assert_eq!(
+            pipeline.process(&[1, 0, 0], &mut output),
+            Err(CodecError::Malformed)
+        );
+        assert_eq!(forwarded.get(), 3);
+        pipeline.process(&[0, 1, 2], &mut output).unwrap();
+        assert_eq!(&output[..], &[1, 2, 3]);
+
+        let (decoder, checksum) = pipeline.inner().inner_stages();
+        assert_eq!(decoder.malformed_errors(), 1);
+        assert_eq!(checksum.malformed_errors(), 0);
+        assert_eq!(pipeline.malformed_errors(), 1);
+    }
+
+    #[cfg(feature = "rle")]
+    #[test]
+    fn delta_rle() {
+        let mut tx = DeltaEncoder::<U16>::new().then::<_, U32>(super::RleCompress);
+        let mut rx = super::RleDecompress.then::<_, U32>(DeltaDecoder::<U16>::new());
+        let mut encoded = Node::<U32, u8>::new();
+        let mut decoded = Node::<U16, u8>::new();
+
+        tx.process(&[5; 16], &mut encoded).unwrap();
+        rx.process(&encoded, &mut decoded).unwrap();
+        assert_eq!(&decoded[..], &[5; 16]);
+
+        let mut frame = [5; 16];
+        frame[15] = 4;
+        encoded.clear();
+        decoded.clear();
+        tx.process(&frame, &mut encoded).unwrap();
+        assert_eq!(&encoded[..], &[0, 1, 242, 0, 0, 1]);
+        rx.process(&encoded, &mut decoded).unwrap();
+        assert_eq!(&decoded[..], &frame);
+    }
+}
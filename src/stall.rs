@@ -0,0 +1,96 @@
+use crate::Clock;
+
+/// Detects when a pipeline stops making progress, e.g. a DMA transfer that never completes,
+/// calling a user callback so it can skip petting the watchdog or dump diagnostics.
+///
+/// It has to be polled periodically with a progress marker, like a count of completed transfers
+/// or the remaining count of the current transfer.
+pub struct StallDetector<C, F> {
+    clock: C,
+    threshold: u32,
+    on_stall: F,
+    progress: u32,
+    since: u32,
+    stalled: bool,
+    stalls: u32,
+}
+
+impl<C, F> StallDetector<C, F>
+where
+    C: Clock,
+    F: FnMut(u32),
+{
+    /// Creates a detector calling `on_stall` with the stall duration when there's pending work
+    /// but the progress marker doesn't change for more than `threshold` ticks.
+    pub fn new(clock: C, threshold: u32, on_stall: F) -> Self {
+        let since = clock.now();
+        Self {
+            clock,
+            threshold,
+            on_stall,
+            progress: 0,
+            since,
+            stalled: false,
+            stalls: 0,
+        }
+    }
+
+    /// Checks for a stall, `busy` tells if there is work pending, `progress` is the current
+    /// progress marker. The callback is called once per stall and `true` is returned while
+    /// stalled.
+    pub fn poll(&mut self, busy: bool, progress: u32) -> bool {
+        let now = self.clock.now();
+        if !busy || progress != self.progress {
+            self.progress = progress;
+            self.since = now;
+            self.stalled = false;
+            return false;
+        }
+
+        let duration = now.wrapping_sub(self.since);
+        if duration > self.threshold && !self.stalled {
+            self.stalled = true;
+            self.stalls = self.stalls.wrapping_add(1);
+            (self.on_stall)(duration);
+        }
+        self.stalled
+    }
+
+    /// Returns how many stalls were detected.
+    #[inline]
+    pub fn stalls(&self) -> u32 {
+        self.stalls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StallDetector;
+    use core::cell::Cell;
+
+    #[test]
+    fn stall() {
+        let time = Cell::new(0);
+        let reported = Cell::new(0);
+        let mut detector = StallDetector::new(|| time.get(), 10, |duration| reported.set(duration));
+
+        assert!(!detector.poll(true, 1));
+        time.set(10);
+        assert!(!detector.poll(true, 1));
+        time.set(11);
+        assert!(detector.poll(true, 1));
+        assert_eq!(reported.get(), 11);
+        time.set(20);
+        assert!(detector.poll(true, 1));
+        assert_eq!(reported.get(), 11);
+
+        // Progress or no pending work clear the stall
+        assert!(!detector.poll(true, 2));
+        time.set(40);
+        assert!(!detector.poll(false, 2));
+        time.set(60);
+        assert!(!detector.poll(false, 2));
+        assert!(!detector.poll(true, 2));
+        assert_eq!(detector.stalls(), 1);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::Notify;
+use core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+const INDEX: u8 = 0b011;
+const FRESH: u8 = 0b100;
+
+/// A cell to share the latest filled node between two contexts, where the newest published node
+/// always wins and the reader always has a valid node available.
+///
+/// It holds two nodes, the published one and the one being read, plus an empty slot used during
+/// `publish`.
+///
+/// Only available on targets with 8 bit atomic swap, which e.g. thumbv6m lacks.
+pub struct Swap<T> {
+    slots: [UnsafeCell<MaybeUninit<T>>; 3],
+    // Index of the published slot and the `FRESH` flag
+    published: AtomicU8,
+    // Only accessed by the `Publisher`
+    empty: UnsafeCell<u8>,
+    // Only accessed by the `Reader`
+    reading: UnsafeCell<u8>,
+}
+
+/// Publishing end of a `Swap`.
+pub struct Publisher<'a, T> {
+    swap: &'a Swap<T>,
+}
+
+/// Reading end of a `Swap`.
+pub struct Reader<'a, T> {
+    swap: &'a Swap<T>,
+}
+
+unsafe impl<T: Send> Sync for Swap<T> {}
+
+impl<T> Swap<T> {
+    /// Creates a new cell, `published` will be given to the reader on its next `read` and
+    /// `reading` is given to it until then.
+    pub const fn new(published: T, reading: T) -> Self {
+        Self {
+            slots: [
+                UnsafeCell::new(MaybeUninit::new(published)),
+                UnsafeCell::new(MaybeUninit::new(reading)),
+                UnsafeCell::new(MaybeUninit::uninit()),
+            ],
+            published: AtomicU8::new(FRESH),
+            empty: UnsafeCell::new(2),
+            reading: UnsafeCell::new(1),
+        }
+    }
+
+    /// Splits the cell into its publishing and reading ends.
+    pub fn split(&mut self) -> (Publisher<'_, T>, Reader<'_, T>) {
+        (Publisher { swap: self }, Reader { swap: self })
+    }
+}
+
+impl<T> Publisher<'_, T> {
+    /// Makes `filled` the node seen by the reader and returns the node that was published before,
+    /// or the one the reader has finished with if it has already taken the previous one.
+    pub fn publish(&mut self, filled: T) -> T {
+        // Safe as the `empty` slot and index are only touched by the publisher
+        unsafe {
+            let empty = *self.swap.empty.get();
+            ptr::write(self.slot(empty), filled);
+
+            let old = self.swap.published.swap(empty | FRESH, Ordering::AcqRel) & INDEX;
+            *self.swap.empty.get() = old;
+            ptr::read(self.slot(old))
+        }
+    }
+
+    /// Publishes `filled` like `publish` and then signals the reader through `notify`.
+    pub fn publish_and_notify(&mut self, filled: T, notify: &impl Notify) -> T {
+        let old = self.publish(filled);
+        notify.notify();
+        old
+    }
+
+    fn slot(&self, index: u8) -> *mut T {
+        self.swap.slots[index as usize].get() as *mut T
+    }
+}
+
+impl<T> Reader<'_, T> {
+    /// Checks if a node was published since the last `read`.
+    pub fn has_fresh(&self) -> bool {
+        self.swap.published.load(Ordering::Relaxed) & FRESH != 0
+    }
+
+    /// Returns the latest published node.
+    pub fn read(&mut self) -> &T {
+        // Safe as the `reading` slot and index are only touched by the reader
+        unsafe {
+            let reading = self.swap.reading.get();
+            if self.has_fresh() {
+                *reading = self.swap.published.swap(*reading, Ordering::AcqRel) & INDEX;
+            }
+            &*(self.swap.slots[*reading as usize].get() as *const T)
+        }
+    }
+}
+
+impl<T> Drop for Swap<T> {
+    fn drop(&mut self) {
+        let published = *self.published.get_mut() & INDEX;
+        let reading = *self.reading.get_mut();
+        for index in [published, reading].iter() {
+            unsafe {
+                ptr::drop_in_place(self.slots[*index as usize].get() as *mut T);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Swap;
+    use core::{
+        cell::Cell,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    #[test]
+    fn latest_wins() {
+        let mut swap = Swap::new(1, 0);
+        let (mut publisher, mut reader) = swap.split();
+        assert!(reader.has_fresh());
+        assert_eq!(*reader.read(), 1);
+        assert!(!reader.has_fresh());
+        assert_eq!(*reader.read(), 1);
+
+        // The reader got back its old node
+        assert_eq!(publisher.publish(2), 0);
+        // Not seen by the reader, so it's given back
+        assert_eq!(publisher.publish(3), 2);
+        assert_eq!(*reader.read(), 3);
+        assert_eq!(publisher.publish(4), 1);
+        assert_eq!(*reader.read(), 4);
+    }
+
+    #[test]
+    fn publish_and_notify() {
+        let mut swap = Swap::new(1, 0);
+        let (mut publisher, mut reader) = swap.split();
+        let flag = AtomicBool::new(false);
+        publisher.publish_and_notify(2, &flag);
+        assert!(flag.load(Ordering::Acquire));
+        assert_eq!(*reader.read(), 2);
+
+        let count = Cell::new(0);
+        publisher.publish_and_notify(3, &|| count.set(count.get() + 1));
+        assert_eq!((count.get(), *reader.read()), (1, 3));
+    }
+
+    #[test]
+    fn drops_held_nodes() {
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut swap = Swap::new(Counted(&drops), Counted(&drops));
+        {
+            let (mut publisher, mut reader) = swap.split();
+            reader.read();
+            drop(publisher.publish(Counted(&drops)));
+            assert_eq!(drops.get(), 1);
+        }
+        drop(swap);
+        assert_eq!(drops.get(), 3);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{Clock, DMANodeMut, Pending, RetransmitBuffer, Sequencer, Stage, Timeout};
+use generic_array::{typenum::marker_traits::Unsigned, ArrayLength};
+
+/// Reason for `WindowSender::send` to give the node back.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendError<T> {
+    /// There are already `window` nodes waiting for acknowledgment.
+    WindowFull(T),
+    /// The payload and the sequence number don't fit in the node.
+    Capacity(T),
+}
+
+/// Sender with at most `window` unacknowledged nodes in flight, built from a `Sequencer` and a
+/// `RetransmitBuffer`, the receiver uses a `SequenceTracker` and acknowledges each sequence
+/// number.
+pub struct WindowSender<D, T, C>
+where
+    D: ArrayLength<Option<Pending<T>>> + Unsigned,
+{
+    sequencer: Sequencer,
+    buffer: RetransmitBuffer<D, T, C>,
+    window: usize,
+    blocked: u32,
+}
+
+impl<D, T, C> WindowSender<D, T, C>
+where
+    D: ArrayLength<Option<Pending<T>>> + Unsigned,
+    T: DMANodeMut<u8>,
+    C: Clock,
+{
+    /// Creates a sender with the given `window`, limited to `D`, and the retransmission
+    /// parameters of `RetransmitBuffer::new`.
+    pub fn new(clock: C, window: usize, timeout: u32, max_retries: u8) -> Self {
+        Self {
+            sequencer: Sequencer::default(),
+            buffer: RetransmitBuffer::new(clock, timeout, max_retries),
+            window: window.min(D::USIZE),
+            blocked: 0,
+        }
+    }
+
+    /// Checks if the window has room for another node.
+    #[inline]
+    pub fn can_send(&self) -> bool {
+        self.buffer.in_flight() < self.window
+    }
+
+    /// Checks if there are no unacknowledged nodes in flight, e.g. before entering a low power
+    /// mode.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        self.buffer.is_idle()
+    }
+
+    /// Clears `node`, fills it with the next sequence number and `payload` and keeps it until
+    /// acknowledged, returns the sequence number and the node to be transferred.
+    pub fn send(&mut self, payload: &[u8], mut node: T) -> Result<(u16, &T), SendError<T>> {
+        if !self.can_send() {
+            self.blocked = self.blocked.wrapping_add(1);
+            return Err(SendError::WindowFull(node));
+        }
+
+        let sequence = self.sequencer.next_sequence();
+        node.clear();
+        if self.sequencer.process(payload, &mut node).is_err() {
+            return Err(SendError::Capacity(node));
+        }
+        match self.buffer.insert(sequence, node) {
+            Ok(node) => Ok((sequence, node)),
+            // Only if the buffer is full, which the window doesn't allow
+            Err(node) => Err(SendError::WindowFull(node)),
+        }
+    }
+
+    /// Releases the node with `sequence`, returning it so it can be reused.
+    #[inline]
+    pub fn ack(&mut self, sequence: u16) -> Option<T> {
+        self.buffer.ack(sequence)
+    }
+
+    /// Looks for a node whose acknowledgment timed out, see `RetransmitBuffer::poll`.
+    #[inline]
+    pub fn poll(&mut self) -> Option<Timeout<'_, T>> {
+        self.buffer.poll()
+    }
+
+    /// Returns how many times a node couldn't be sent because the window was full.
+    #[inline]
+    pub fn blocked(&self) -> u32 {
+        self.blocked
+    }
+
+    /// Returns the retransmission buffer, e.g. to read its counters.
+    #[inline]
+    pub fn buffer(&self) -> &RetransmitBuffer<D, T, C> {
+        &self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SendError, WindowSender};
+    use crate::{typenum::consts::*, Node, Timeout};
+    use core::cell::Cell;
+
+    #[test]
+    fn window() {
+        let time = Cell::new(0);
+        let mut sender = WindowSender::<U4, Node<U4, u8>, _>::new(|| time.get(), 2, 10, 0);
+
+        let (sequence, node) = sender.send(&[7], Node::new()).unwrap();
+        assert_eq!((sequence, &node[..]), (0, &[0, 0, 7][..]));
+        assert!(matches!(
+            sender.send(&[0; 3], Node::new()),
+            Err(SendError::Capacity(_))
+        ));
+        sender.send(&[8], Node::new()).unwrap();
+        assert!(!sender.can_send());
+        assert!(matches!(
+            sender.send(&[9], Node::new()),
+            Err(SendError::WindowFull(_))
+        ));
+        assert_eq!(sender.blocked(), 1);
+
+        assert_eq!(&sender.ack(0).unwrap()[..], &[0, 0, 7]);
+        let (sequence, _) = sender.send(&[9], Node::new()).unwrap();
+        assert_eq!(sequence, 2);
+
+        time.set(10);
+        assert!(matches!(sender.poll(), Some(Timeout::GaveUp(_, _))));
+        assert!(matches!(sender.poll(), Some(Timeout::GaveUp(_, _))));
+        assert!(sender.poll().is_none());
+        assert!(sender.is_idle());
+        assert_eq!(sender.buffer().failed(), 2);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{encode_bits, BitOrder, CapacityError, DMANodeMut, SymbolTable};
+
+/// Builds the timer compare values to drive WS2812/SK6812 LEDs by DMA into a PWM channel, one
+/// value per bit at 800 kHz, the most significant bit of each color first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ws2812 {
+    zero: u16,
+    one: u16,
+    reset: usize,
+}
+
+impl Ws2812 {
+    /// Creates a builder for a timer with `period` ticks per 1.25 us bit, with high times of
+    /// 0.4 us for a `0` and 0.8 us for a `1`, and a reset gap of 50 us.
+    pub const fn new(period: u16) -> Self {
+        Self {
+            zero: (period as u32 * 8 / 25) as u16,
+            one: (period as u32 * 16 / 25) as u16,
+            reset: 40,
+        }
+    }
+
+    /// Changes the length of the reset gap in bit periods, newer parts need 280 us, i.e. 224
+    /// periods.
+    pub const fn reset_periods(mut self, periods: usize) -> Self {
+        self.reset = periods;
+        self
+    }
+
+    /// Appends the values for RGB pixels, sent in the GRB order of the LEDs. Nothing is written if
+    /// they don't fit.
+    pub fn push_rgb(
+        &self,
+        dest: &mut impl DMANodeMut<u16>,
+        pixels: &[[u8; 3]],
+    ) -> Result<(), CapacityError> {
+        if pixels.len() * 24 > dest.free() {
+            return Err(CapacityError);
+        }
+        for &[r, g, b] in pixels {
+            self.push_colors(dest, &[g, r, b])?;
+        }
+        Ok(())
+    }
+
+    /// Appends the values for RGBW pixels, sent in the GRBW order of the LEDs. Nothing is written
+    /// if they don't fit.
+    pub fn push_rgbw(
+        &self,
+        dest: &mut impl DMANodeMut<u16>,
+        pixels: &[[u8; 4]],
+    ) -> Result<(), CapacityError> {
+        if pixels.len() * 32 > dest.free() {
+            return Err(CapacityError);
+        }
+        for &[r, g, b, w] in pixels {
+            self.push_colors(dest, &[g, r, b, w])?;
+        }
+        Ok(())
+    }
+
+    /// Appends the reset gap, which latches the colors sent before it. Nothing is written if it
+    /// doesn't fit.
+    pub fn push_reset(&self, dest: &mut impl DMANodeMut<u16>) -> Result<(), CapacityError> {
+        if self.reset > dest.free() {
+            return Err(CapacityError);
+        }
+        for _ in 0..self.reset {
+            dest.write_slice(&[0]);
+        }
+        Ok(())
+    }
+
+    fn push_colors(
+        &self,
+        dest: &mut impl DMANodeMut<u16>,
+        colors: &[u8],
+    ) -> Result<(), CapacityError> {
+        let table = SymbolTable {
+            zero: &[self.zero],
+            one: &[self.one],
+        };
+        encode_bits(colors, dest, &table, BitOrder::MsbFirst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ws2812;
+    use crate::{typenum::consts::*, CapacityError, DMANodeRef, Node};
+
+    #[test]
+    fn pixels() {
+        // 72 MHz timer
+        let leds = Ws2812::new(90).reset_periods(2);
+        assert_eq!((leds.zero, leds.one), (28, 57));
+
+        let mut node = Node::<U64, u16>::new();
+        leds.push_rgb(&mut node, &[[0x00, 0xFF, 0x80]]).unwrap();
+        leds.push_reset(&mut node).unwrap();
+        assert_eq!(&node[..8], &[57; 8]);
+        assert_eq!(&node[8..16], &[28; 8]);
+        assert_eq!(&node[16..24], &[57, 28, 28, 28, 28, 28, 28, 28]);
+        assert_eq!(&node[24..], &[0, 0]);
+
+        assert_eq!(leds.push_rgbw(&mut node, &[[0; 4]; 2]), Err(CapacityError));
+        leds.push_rgbw(&mut node, &[[0, 0, 0, 1]]).unwrap();
+        assert_eq!(node.len(), 58);
+        assert_eq!(node[57], 57);
+    }
+}